@@ -0,0 +1,11 @@
+BEGIN;
+
+-- A price (in pence) for a game which we'd be happy to pay, used to alert when a game becomes
+-- cheap enough to be worth buying
+CREATE TABLE IF NOT EXISTS target_price (
+  app_id BIGINT PRIMARY KEY,
+  price INTEGER NOT NULL,
+  set_at TIMESTAMP DEFAULT NOW()
+);
+
+COMMIT;
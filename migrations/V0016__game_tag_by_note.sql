@@ -0,0 +1,11 @@
+BEGIN;
+
+-- Tags come from notion, and noted games may not have an app ID yet, so relate tags to the note
+-- rather than only to the steam game. app_id is kept alongside for convenience in queries.
+DELETE FROM game_tag;
+ALTER TABLE game_tag ADD note_id VARCHAR(64) NOT NULL REFERENCES noted_game(note_id) ON DELETE CASCADE;
+ALTER TABLE game_tag ADD CONSTRAINT game_tag_note_id_tag_id_key UNIQUE (note_id, tag_id);
+
+ALTER TABLE tag ADD CONSTRAINT tag_name_key UNIQUE (name);
+
+COMMIT;
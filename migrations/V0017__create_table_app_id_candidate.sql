@@ -0,0 +1,18 @@
+BEGIN;
+
+-- Possible steam games for notes we couldn't find an exact name match for, found by fuzzy name
+-- matching. Confident matches are applied automatically and recorded as accepted; the rest wait
+-- here to be accepted or rejected by hand.
+CREATE TABLE IF NOT EXISTS app_id_candidate (
+  id SERIAL PRIMARY KEY,
+  note_id VARCHAR(64) NOT NULL REFERENCES noted_game(note_id) ON DELETE CASCADE,
+  note_name VARCHAR(256) NOT NULL,
+  app_id BIGINT NOT NULL,
+  score DOUBLE PRECISION NOT NULL,
+  status VARCHAR(16) NOT NULL DEFAULT 'pending',
+  recorded TIMESTAMP NOT NULL DEFAULT NOW(),
+  UNIQUE (note_id, app_id)
+);
+CREATE INDEX IF NOT EXISTS app_id_candidate_status ON app_id_candidate USING BTREE(status);
+
+COMMIT;
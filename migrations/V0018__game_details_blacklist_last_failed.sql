@@ -0,0 +1,6 @@
+BEGIN;
+
+-- Record when a game last failed so retries can back off, rather than counting failures alone
+ALTER TABLE game_details_blacklist ADD COLUMN IF NOT EXISTS last_failed TIMESTAMP DEFAULT NOW();
+
+COMMIT;
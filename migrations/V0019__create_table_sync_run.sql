@@ -0,0 +1,25 @@
+BEGIN;
+
+-- History of syncs, to tell whether they're running as expected and how fresh the data is
+CREATE TABLE IF NOT EXISTS sync_run (
+  id SERIAL PRIMARY KEY,
+  started TIMESTAMP NOT NULL,
+  finished TIMESTAMP,
+  status VARCHAR(16) NOT NULL DEFAULT 'running',
+  error TEXT
+);
+CREATE INDEX IF NOT EXISTS sync_run_started ON sync_run USING BTREE(started);
+
+CREATE TABLE IF NOT EXISTS sync_run_phase (
+  id SERIAL PRIMARY KEY,
+  run_id INTEGER NOT NULL REFERENCES sync_run(id) ON DELETE CASCADE,
+  phase VARCHAR(32) NOT NULL,
+  started TIMESTAMP NOT NULL,
+  finished TIMESTAMP NOT NULL,
+  status VARCHAR(16) NOT NULL,
+  item_count INTEGER,
+  error TEXT
+);
+CREATE INDEX IF NOT EXISTS sync_run_phase_run_id ON sync_run_phase USING BTREE(run_id);
+
+COMMIT;
@@ -0,0 +1,24 @@
+BEGIN;
+
+-- Every event reported by a sync, so reports can be replayed and notifiers can catch up on
+-- anything they haven't yet delivered
+CREATE TABLE IF NOT EXISTS sync_event (
+  id BIGSERIAL PRIMARY KEY,
+  run_id INTEGER REFERENCES sync_run(id) ON DELETE SET NULL,
+  app_id BIGINT NOT NULL,
+  event_type VARCHAR(32) NOT NULL,
+  payload JSONB NOT NULL,
+  recorded TIMESTAMP NOT NULL
+);
+CREATE INDEX IF NOT EXISTS sync_event_recorded ON sync_event USING BTREE(recorded);
+CREATE INDEX IF NOT EXISTS sync_event_app_id ON sync_event USING BTREE(app_id);
+
+-- Which notifiers have delivered which events
+CREATE TABLE IF NOT EXISTS sync_event_delivery (
+  event_id BIGINT NOT NULL REFERENCES sync_event(id) ON DELETE CASCADE,
+  notifier VARCHAR(64) NOT NULL,
+  delivered TIMESTAMP NOT NULL,
+  PRIMARY KEY (event_id, notifier)
+);
+
+COMMIT;
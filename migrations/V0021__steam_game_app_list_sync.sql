@@ -0,0 +1,15 @@
+BEGIN;
+
+-- What kind of app each steam_game is, and when steam last changed it, from the
+-- IStoreService/GetAppList catalogue
+ALTER TABLE steam_game ADD COLUMN IF NOT EXISTS app_type VARCHAR(16);
+ALTER TABLE steam_game ADD COLUMN IF NOT EXISTS last_modified TIMESTAMP;
+
+-- High-water mark for each type of app in the catalogue, so each sync only asks for apps
+-- which have been added or changed since the last one
+CREATE TABLE IF NOT EXISTS app_list_sync (
+  app_type VARCHAR(16) PRIMARY KEY,
+  last_modified TIMESTAMP NOT NULL
+);
+
+COMMIT;
@@ -0,0 +1,47 @@
+BEGIN;
+
+-- More of what appdetails tells us about each game
+ALTER TABLE game_details ADD COLUMN IF NOT EXISTS app_type VARCHAR(16);
+ALTER TABLE game_details ADD COLUMN IF NOT EXISTS is_free BOOLEAN;
+ALTER TABLE game_details ADD COLUMN IF NOT EXISTS header_image TEXT;
+
+-- Which version of the metadata below has been stored for each game; details recorded before
+-- any is stored are 0, and are fetched again to fill it in
+ALTER TABLE game_details ADD COLUMN IF NOT EXISTS metadata_version SMALLINT NOT NULL DEFAULT 0;
+
+-- Genres keep the IDs steam gives them
+CREATE TABLE IF NOT EXISTS genre (
+  id INTEGER PRIMARY KEY,
+  description VARCHAR(128) NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS game_genre (
+  app_id BIGINT NOT NULL,
+  genre_id INTEGER NOT NULL REFERENCES genre(id),
+  PRIMARY KEY (app_id, genre_id)
+);
+CREATE INDEX IF NOT EXISTS game_genre_genre_id ON game_genre USING BTREE(genre_id);
+
+-- Developers and publishers, which steam only gives as names
+CREATE TABLE IF NOT EXISTS company (
+  id SERIAL PRIMARY KEY,
+  name TEXT NOT NULL UNIQUE
+);
+
+-- role is either 'developer' or 'publisher'
+CREATE TABLE IF NOT EXISTS game_company (
+  app_id BIGINT NOT NULL,
+  company_id INTEGER NOT NULL REFERENCES company(id),
+  role VARCHAR(16) NOT NULL,
+  PRIMARY KEY (app_id, company_id, role)
+);
+CREATE INDEX IF NOT EXISTS game_company_company_id ON game_company USING BTREE(company_id);
+
+-- One of 'windows', 'mac' or 'linux'
+CREATE TABLE IF NOT EXISTS game_platform (
+  app_id BIGINT NOT NULL,
+  platform VARCHAR(16) NOT NULL,
+  PRIMARY KEY (app_id, platform)
+);
+
+COMMIT;
@@ -0,0 +1,17 @@
+BEGIN;
+
+-- Every store category steam lists a game under, e.g. co-op, achievements, cloud saves or
+-- Remote Play Together, keeping the IDs steam gives them
+CREATE TABLE IF NOT EXISTS category (
+  id INTEGER PRIMARY KEY,
+  description VARCHAR(128) NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS game_category (
+  app_id BIGINT NOT NULL,
+  category_id INTEGER NOT NULL REFERENCES category(id),
+  PRIMARY KEY (app_id, category_id)
+);
+CREATE INDEX IF NOT EXISTS game_category_category_id ON game_category USING BTREE(category_id);
+
+COMMIT;
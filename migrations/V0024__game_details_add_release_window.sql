@@ -0,0 +1,12 @@
+BEGIN;
+
+-- The window release_estimate falls in: from release_earliest up to, but not including,
+-- release_latest, and whether release_date gave a day, month, quarter, half or year.
+-- Both bounds are NULL when the precision is unknown.
+ALTER TABLE game_details ADD release_earliest TIMESTAMP DEFAULT NULL;
+ALTER TABLE game_details ADD release_latest TIMESTAMP DEFAULT NULL;
+ALTER TABLE game_details ADD release_precision VARCHAR(16) DEFAULT NULL;
+CREATE INDEX IF NOT EXISTS game_details_release_earliest ON game_details USING BTREE(release_earliest);
+CREATE INDEX IF NOT EXISTS game_details_release_latest ON game_details USING BTREE(release_latest);
+
+COMMIT;
@@ -0,0 +1,11 @@
+BEGIN;
+
+-- How the release date moved: postponed, brought_forward, narrowed, widened, tba or changed.
+-- Classified by the sync from the release windows of the old and new text.
+ALTER TABLE release_update ADD change VARCHAR(16) DEFAULT NULL;
+
+-- Earlier updates are left NULL, for the next sync to classify from their text the same way
+
+CREATE INDEX IF NOT EXISTS release_update_change ON release_update USING BTREE(change);
+
+COMMIT;
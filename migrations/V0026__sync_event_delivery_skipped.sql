@@ -0,0 +1,7 @@
+BEGIN;
+
+-- Events a notifier's filter left out are recorded too, so they're not considered again, but
+-- as skipped: nothing was sent, so they don't count towards when it last delivered
+ALTER TABLE sync_event_delivery ADD COLUMN IF NOT EXISTS skipped BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMIT;
@@ -0,0 +1,22 @@
+-- Wishlisted or noted games which could be released this month, most certain first. A game
+-- dated "Q4" or "2025" shows up every month it might land in, not just at the end.
+SELECT
+  sg.name,
+  gd.release_date,
+  gd.release_precision,
+  gd.release_earliest,
+  gd.release_latest
+FROM
+  game_details gd
+  LEFT JOIN steam_game sg ON gd.app_id = sg.app_id
+WHERE
+  gd.is_released = FALSE AND
+  gd.release_earliest < date_trunc('month', NOW()) + INTERVAL '1 month' AND
+  gd.release_latest > date_trunc('month', NOW()) AND
+  (
+    gd.app_id IN (SELECT app_id FROM wishlist WHERE deleted IS NULL) OR
+    gd.app_id IN (SELECT app_id FROM noted_game WHERE app_id IS NOT NULL)
+  )
+ORDER BY
+  gd.release_latest - gd.release_earliest,
+  gd.release_earliest
@@ -0,0 +1,15 @@
+-- Owned games listed under a given store category, e.g. 44 for Remote Play Together, 22 for
+-- achievements or 23 for cloud saves; see the category table for the rest
+SELECT
+  sg.app_id,
+  sg.name,
+  c.description category
+FROM
+  owned_game og
+  JOIN game_category gc ON og.app_id = gc.app_id
+  JOIN category c ON gc.category_id = c.id
+  LEFT JOIN steam_game sg ON og.app_id = sg.app_id
+WHERE
+  c.id = 44
+ORDER BY
+  sg.name
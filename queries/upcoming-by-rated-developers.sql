@@ -0,0 +1,39 @@
+-- Upcoming games from developers whose most recent released game I rated 4 or more on notion
+-- Developers are taken from steam's appdetails, so only games we've fetched details for count
+WITH rated AS (
+  SELECT DISTINCT ON (gc.company_id)
+    gc.company_id,
+    sg.name last_game,
+    ng.my_rating
+  FROM
+    noted_game ng
+    JOIN game_company gc ON ng.app_id = gc.app_id AND gc.role = 'developer'
+    JOIN game_details gd ON ng.app_id = gd.app_id
+    LEFT JOIN steam_game sg ON ng.app_id = sg.app_id
+  WHERE
+    gd.is_released AND
+    ng.my_rating IS NOT NULL
+  ORDER BY
+    gc.company_id,
+    gd.release_estimate DESC NULLS LAST
+)
+SELECT
+  sg.app_id,
+  sg.name,
+  c.name developer,
+  r.last_game,
+  r.my_rating,
+  gd.release_date
+FROM
+  rated r
+  JOIN company c ON r.company_id = c.id
+  JOIN game_company gc ON r.company_id = gc.company_id AND gc.role = 'developer'
+  JOIN game_details gd ON gc.app_id = gd.app_id
+  LEFT JOIN steam_game sg ON gc.app_id = sg.app_id
+WHERE
+  NOT gd.is_released AND
+  r.my_rating >= 4
+ORDER BY
+  r.my_rating DESC,
+  gd.release_estimate NULLS LAST,
+  sg.name
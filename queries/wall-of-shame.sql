@@ -7,7 +7,8 @@ SELECT
   ru.new_text,
   ru.prev_estimate,
   ru.new_estimate,
-  COALESCE(ru.new_estimate > ru.prev_estimate, ru.prev_estimate IS NOT NULL AND ru.new_estimate IS NULL, FALSE) delayed,
+  ru.change,
+  ru.change IN ('postponed', 'widened', 'tba') delayed,
   ru.recorded
 FROM
   release_update ru
@@ -1,5 +1,14 @@
+pub mod blacklist;
+pub mod daemon;
+pub mod events;
+pub mod feed;
 pub mod ignore;
+pub mod matching;
+pub mod release_dates;
+pub mod reputation;
+pub mod status;
 pub mod sync;
+pub mod target_price;
 
 use clap::Parser;
 
@@ -8,14 +17,32 @@ use clap::Parser;
 #[command(version = "0.1.0")]
 enum Cli {
     Sync(sync::RunSync),
+    Daemon(daemon::RunDaemon),
     IgnoreGame(ignore::RunIgnoreGame),
+    TargetPrice(target_price::RunTargetPrice),
+    Match(matching::RunMatch),
+    Blacklist(blacklist::RunBlacklist),
+    Status(status::RunStatus),
+    Events(events::RunEvents),
+    Feed(feed::RunFeed),
+    ReleaseDates(release_dates::RunReleaseDates),
+    Reputation(reputation::RunReputation),
 }
 
 impl Cli {
     async fn run(&self) {
         match self {
             Self::Sync(cmd) => cmd.run().await,
+            Self::Daemon(cmd) => cmd.run().await,
             Self::IgnoreGame(cmd) => cmd.run().await,
+            Self::TargetPrice(cmd) => cmd.run().await,
+            Self::Match(cmd) => cmd.run().await,
+            Self::Blacklist(cmd) => cmd.run().await,
+            Self::Status(cmd) => cmd.run().await,
+            Self::Events(cmd) => cmd.run().await,
+            Self::Feed(cmd) => cmd.run().await,
+            Self::ReleaseDates(cmd) => cmd.run().await,
+            Self::Reputation(cmd) => cmd.run().await,
         }
     }
 }
@@ -0,0 +1,90 @@
+use std::path::PathBuf;
+
+use chrono::Utc;
+use clap::Parser;
+
+use crate::config;
+use crate::db;
+use crate::db::repo::{GameDetailsBlacklistHandling, Repo};
+use crate::models::game::{GameId, BLACKLIST_MAX_FAILURES};
+
+#[derive(Debug, Parser)]
+pub struct RunBlacklist {
+    #[arg(short, long)]
+    pub config_file: Option<PathBuf>,
+    #[arg(
+      long, conflicts_with = "clear",
+      help = "App ID of a game to remove from the blacklist, so its details are fetched next sync"
+    )]
+    pub reset: Option<String>,
+    #[arg(long, help = "Remove every game from the blacklist")]
+    pub clear: bool,
+}
+
+impl RunBlacklist {
+    /// List games steam hasn't returned usable details for, or remove them from the blacklist
+    pub async fn run(&self) {
+        let conf = config::read(self.config_file.as_ref());
+        let mut db_client = db::connect(&conf.db.connection_string()).await;
+        db::migrate(&mut db_client).await;
+
+        let repo = Repo::new(db_client);
+
+        if let Some(game) = &self.reset {
+            let id = GameId::try_from(game.as_ref()).unwrap();
+
+            println!("Removing game {} from the blacklist...", &id);
+            match repo.delete_blacklist_entries(&[id]).await {
+                Ok(0) => println!("Game {} wasn't blacklisted.", &id),
+                Ok(_) => (),
+                Err(e) => panic!("Error while resetting blacklist entry: {}", e),
+            }
+            return;
+        }
+
+        if self.clear {
+            match repo.clear_blacklist().await {
+                Ok(count) => println!("Removed {} games from the blacklist.", count),
+                Err(e) => panic!("Error while clearing blacklist: {}", e),
+            }
+            return;
+        }
+
+        let entries = match repo.get_blacklist().await {
+            Ok(e) => e,
+            Err(e) => panic!("Error while reading blacklist: {}", e),
+        };
+
+        if entries.is_empty() {
+            println!("No games are blacklisted.");
+            return;
+        }
+
+        let now = Utc::now();
+
+        println!("{:>8}  {:<40}  {:>8}  {:<16}  {:<16}", "App ID", "Name", "Failures", "Last failed", "Next attempt");
+        for e in entries {
+            let last_failed = {
+                e.last_failed
+                    .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or("-".to_string())
+            };
+            let next_attempt = match e.retry_after() {
+                None => "retired".to_string(),
+                Some(t) if t <= now => "next sync".to_string(),
+                Some(t) => t.format("%Y-%m-%d %H:%M").to_string(),
+            };
+
+            println!(
+                "{:>8}  {:<40}  {:>8}  {:<16}  {:<16}",
+                e.id, e.name.as_deref().unwrap_or("?"), e.failure_count, last_failed, next_attempt
+            );
+        }
+
+        println!();
+        println!(
+            "Games are retired after {} failures; use --reset <app ID> to try one again.",
+            BLACKLIST_MAX_FAILURES
+        );
+    }
+}
@@ -0,0 +1,140 @@
+use std::path::PathBuf;
+
+use chrono::Utc;
+use clap::Parser;
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::watch;
+
+use crate::config;
+use crate::daemon::Schedule;
+use crate::db;
+use crate::db::repo::{Repo, SyncRunHandling};
+use crate::models::sync::SyncPhase;
+
+use super::sync::{new_sync, publish, SyncReport};
+
+#[derive(Debug, Parser)]
+pub struct RunDaemon {
+    #[arg(short, long)]
+    pub config_file: Option<PathBuf>,
+}
+
+// How long to sleep when no phases are scheduled at all
+const IDLE_WAIT: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+// How long to wait before trying again when postgres can't be reconnected to
+const RECONNECT_WAIT: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Watch for SIGTERM or SIGINT, flagging that the daemon should stop once it's safe to
+fn shutdown_signal() -> watch::Receiver<bool> {
+    let (tx, rx) = watch::channel(false);
+
+    let mut term = signal(SignalKind::terminate()).expect("Failed to listen for SIGTERM");
+    let mut int = signal(SignalKind::interrupt()).expect("Failed to listen for SIGINT");
+
+    tokio::spawn(async move {
+        tokio::select! {
+            _ = term.recv() => println!("Received SIGTERM, shutting down"),
+            _ = int.recv() => println!("Received SIGINT, shutting down"),
+        }
+        let _ = tx.send(true);
+    });
+
+    rx
+}
+
+impl RunDaemon {
+    /// Keep running, syncing each phase on its own schedule until told to stop
+    pub async fn run(&self) {
+        let conf = config::read(self.config_file.as_ref());
+        let mut shutdown = shutdown_signal();
+
+        let mut db_client = db::connect(&conf.db.connection_string()).await;
+        db::migrate(&mut db_client).await;
+
+        let mut sync = new_sync(&conf, Repo::new(db_client), false);
+
+        // Pick up where the last sync left off rather than running everything at startup
+        let last_synced = match sync.repo.get_last_successful_phases().await {
+            Ok(l) => l,
+            Err(e) => panic!("Error while reading sync history: {}", e),
+        };
+        let mut schedule = match Schedule::from_config(&conf.daemon, &last_synced, &Utc::now()) {
+            Ok(s) => s,
+            Err(e) => panic!("{}", e),
+        };
+
+        println!("rusteam daemon started");
+
+        while !*shutdown.borrow() {
+            // A restart or network blip leaves the client closed for good, so start afresh
+            if sync.repo.is_closed() {
+                eprintln!("Lost connection to the database; reconnecting");
+
+                match db::try_connect(&conf.db.connection_string()).await {
+                    Ok(db_client) => sync = new_sync(&conf, Repo::new(db_client), false),
+                    Err(e) => {
+                        eprintln!("Failed to reconnect to the database: {}; retrying in {:?}", e, RECONNECT_WAIT);
+
+                        tokio::select! {
+                            _ = tokio::time::sleep(RECONNECT_WAIT) => {},
+                            _ = shutdown.changed() => {},
+                        }
+                        continue;
+                    },
+                }
+            }
+
+            let due = schedule.due(&Utc::now());
+            let mut events = vec![];
+
+            for phase in due.iter().copied() {
+                // Let the phase in progress finish, but don't start any more
+                if *shutdown.borrow() {
+                    break;
+                }
+
+                println!("Running scheduled {} sync", phase);
+                match sync.run_phases(&[phase]).await {
+                    Ok(e) => {
+                        events.extend(e);
+                        schedule.record(phase, true, &Utc::now());
+                    },
+                    Err(e) => {
+                        eprintln!("Scheduled {} sync failed: {}", phase, e);
+                        schedule.record(phase, false, &Utc::now());
+                    },
+                }
+            }
+
+            if !due.is_empty() {
+                // Also retries deliveries which failed after earlier phases
+                publish(&conf, &sync.repo).await;
+
+                let skipped_details = {
+                    if due.contains(&SyncPhase::GameDetails) { sync.skipped_details.as_slice() } else { &[] }
+                };
+                let skipped_prices = {
+                    if due.contains(&SyncPhase::Prices) { sync.skipped_prices.as_slice() } else { &[] }
+                };
+                if !events.is_empty() || !skipped_details.is_empty() || !skipped_prices.is_empty() {
+                    SyncReport::new(&sync.repo).run(&events, skipped_details, skipped_prices).await;
+                }
+            }
+
+            let wait = {
+                schedule
+                    .next_due()
+                    .map(|t| (t - Utc::now()).to_std().unwrap_or_default())
+                    .unwrap_or(IDLE_WAIT)
+            };
+
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {},
+                _ = shutdown.changed() => {},
+            }
+        }
+
+        println!("rusteam daemon stopped");
+    }
+}
@@ -0,0 +1,79 @@
+use std::path::PathBuf;
+
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+use clap::Parser;
+
+use crate::config;
+use crate::db;
+use crate::db::repo::{Repo, SteamGamesHandling, SyncEventHandling};
+use crate::models::game::GameId;
+
+#[derive(Debug, Parser)]
+pub struct RunEvents {
+    #[arg(short, long)]
+    pub config_file: Option<PathBuf>,
+    #[arg(
+      short, long, default_value = "7d",
+      help = "Show events since a date like 2024-05-01, or for a period like 12h, 7d or 4w"
+    )]
+    pub since: String,
+}
+
+/// Parse a point in time given either as a date or as a period before now
+pub fn parse_since(s: &str, now: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Some(date.and_hms_opt(0, 0, 0)?.and_utc());
+    }
+
+    // The unit may not be a single byte, e.g. a mistyped "3д", so split on a char boundary
+    let (unit_start, _) = s.char_indices().last()?;
+    let (amount, unit) = s.split_at(unit_start);
+    let amount = amount.parse::<i64>().ok()?;
+    let period = match unit {
+        "h" => TimeDelta::try_hours(amount)?,
+        "d" => TimeDelta::try_days(amount)?,
+        "w" => TimeDelta::try_weeks(amount)?,
+        _ => return None,
+    };
+
+    now.checked_sub_signed(period)
+}
+
+impl RunEvents {
+    /// Replay events reported by past syncs
+    pub async fn run(&self) {
+        let since = {
+            parse_since(&self.since, &Utc::now())
+                .expect("Invalid --since, expected a date like 2024-05-01 or a period like 7d")
+        };
+
+        let conf = config::read(self.config_file.as_ref());
+        let mut db_client = db::connect(&conf.db.connection_string()).await;
+        db::migrate(&mut db_client).await;
+
+        let repo = Repo::new(db_client);
+
+        let events = match repo.get_sync_events_since(&since).await {
+            Ok(e) => e,
+            Err(e) => panic!("Error while reading sync events: {}", e),
+        };
+
+        if events.is_empty() {
+            println!("No events since {}.", since.format("%Y-%m-%d %H:%M"));
+            return;
+        }
+
+        let ids: Vec<GameId> = events.iter().map(|e| *e.event.game()).collect();
+        let names_by_id = match repo.get_game_names_by_id(&ids).await {
+            Ok(n) => n,
+            Err(e) => panic!("Error while reading game names: {}", e),
+        };
+
+        for e in &events {
+            let game = e.event.game();
+            let name = names_by_id.get(game).cloned().unwrap_or(game.to_string());
+
+            println!("{}  {}", e.recorded.format("%Y-%m-%d %H:%M"), e.event.describe(&name));
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use crate::config;
+use crate::db;
+use crate::db::repo::Repo;
+use crate::feed;
+
+#[derive(Debug, Parser)]
+pub struct RunFeed {
+    #[arg(short, long)]
+    pub config_file: Option<PathBuf>,
+    #[arg(short, long, help = "Where to write the feed; defaults to the path set in [feed] config")]
+    pub output: Option<PathBuf>,
+    #[arg(short, long, help = "How many days of news to include")]
+    pub days: Option<i64>,
+}
+
+impl RunFeed {
+    /// Write an Atom feed of release date changes and releases
+    pub async fn run(&self) {
+        let conf = config::read(self.config_file.as_ref());
+        let mut db_client = db::connect(&conf.db.connection_string()).await;
+        db::migrate(&mut db_client).await;
+
+        let repo = Repo::new(db_client);
+
+        let path = {
+            self.output
+                .clone()
+                .or(conf.feed.as_ref().map(|f| PathBuf::from(&f.path)))
+                .expect("No output path given, and no [feed] path in config")
+        };
+        let title = conf.feed.as_ref().map(|f| f.title.as_str()).unwrap_or("rusteam release news");
+        let self_url = conf.feed.as_ref().and_then(|f| f.self_url.as_deref());
+        let days = self.days.or(conf.feed.as_ref().map(|f| f.days)).unwrap_or(90);
+
+        match feed::write_feed(&repo, &path, title, self_url, days).await {
+            Ok(count) => println!("Wrote {} entries to {}", count, path.display()),
+            Err(e) => panic!("Error while writing feed: {}", e),
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use crate::config;
+use crate::db;
+use crate::db::repo::{AppIdCandidateHandling, Repo};
+use crate::models::game::CandidateStatus;
+use crate::notion::{NotionGamesRepo, NotionHandling};
+
+#[derive(Debug, Parser)]
+pub struct RunMatch {
+    #[arg(short, long)]
+    pub config_file: Option<PathBuf>,
+    #[arg(
+      long, conflicts_with = "reject",
+      help = "Accept a suggested match by ID, writing the app ID to notion"
+    )]
+    pub accept: Option<i32>,
+    #[arg(long, help = "Reject a suggested match by ID so it isn't suggested again")]
+    pub reject: Option<i32>,
+}
+
+impl RunMatch {
+    /// List pending app ID suggestions for notes without one, or accept / reject a suggestion
+    pub async fn run(&self) {
+        let conf = config::read(self.config_file.as_ref());
+        let mut db_client = db::connect(&conf.db.connection_string()).await;
+        db::migrate(&mut db_client).await;
+
+        let repo = Repo::new(db_client);
+
+        if let Some(id) = self.accept {
+            let candidate = match repo.get_candidate(id).await {
+                Ok(Some(c)) => c,
+                Ok(None) => panic!("No suggested match with ID {}", id),
+                Err(e) => panic!("Error while reading suggested match: {}", e),
+            };
+
+            let notion = NotionGamesRepo::new(
+                &conf.notion.api_key,
+                &conf.notion.database_id,
+                &conf.notion.api_hoststring
+            );
+
+            let app_id: String = candidate.app_id.into();
+            if let Err(e) = notion.set_game_details(&candidate.note_id, &app_id, &candidate.note_name) {
+                panic!("Error while writing app ID to notion: {}", e)
+            }
+            if let Err(e) = repo.resolve_candidate(id, &CandidateStatus::Accepted).await {
+                panic!("Error while accepting suggested match: {}", e)
+            }
+            return;
+        }
+
+        if let Some(id) = self.reject {
+            println!("Rejecting suggested match {}...", id);
+            if let Err(e) = repo.resolve_candidate(id, &CandidateStatus::Rejected).await {
+                panic!("Error while rejecting suggested match: {}", e)
+            }
+            return;
+        }
+
+        let pending = match repo.get_pending_candidates().await {
+            Ok(p) => p,
+            Err(e) => panic!("Error while reading suggested matches: {}", e),
+        };
+
+        if pending.is_empty() {
+            println!("No suggested matches waiting for review.");
+            return;
+        }
+
+        println!("{:>6}  {:<40}  {:<40}  {:>8}  {:>5}", "ID", "Note", "Steam game", "App ID", "Score");
+        for (id, c) in pending {
+            println!(
+                "{:>6}  {:<40}  {:<40}  {:>8}  {:>5.2}",
+                id, c.note_name, c.app_name, c.app_id, c.score
+            );
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use crate::config;
+use crate::db;
+use crate::db::repo::{GameDetailsHandling, Repo};
+use crate::models::game::ReleaseWindow;
+use crate::steam::release_date::parse_release_window;
+
+#[derive(Debug, Parser)]
+pub struct RunReleaseDates {
+    #[arg(short, long)]
+    pub config_file: Option<PathBuf>,
+    #[arg(long, help = "Show how every release date is parsed, not just the ones which can't be")]
+    pub all: bool,
+}
+
+fn format_window(window: &ReleaseWindow) -> String {
+    match (window.earliest, window.latest) {
+        (Some(e), Some(l)) => format!("{} to {}", e.format("%Y-%m-%d"), l.format("%Y-%m-%d")),
+        _ => "-".to_string(),
+    }
+}
+
+impl RunReleaseDates {
+    /// List release date texts recorded in game_details which the release date parser doesn't
+    /// understand, so it can be taught them
+    pub async fn run(&self) {
+        let conf = config::read(self.config_file.as_ref());
+        let mut db_client = db::connect(&conf.db.connection_string()).await;
+        db::migrate(&mut db_client).await;
+
+        let repo = Repo::new(db_client);
+
+        let texts = match repo.get_release_date_texts().await {
+            Ok(t) => t,
+            Err(e) => panic!("Error while reading release dates: {}", e),
+        };
+
+        let mut unparsed = 0;
+
+        println!("{:>6}  {:<30}  {:<9}  {:<24}  Example", "Games", "Release date", "Precision", "Window");
+        for t in &texts {
+            let window = parse_release_window(&t.text);
+            if window.is_none() {
+                unparsed += 1;
+            } else if !self.all {
+                continue;
+            }
+
+            let example = format!("{} ({})", t.example_name.as_deref().unwrap_or("?"), t.example);
+
+            println!(
+                "{:>6}  {:<30}  {:<9}  {:<24}  {}",
+                t.game_count,
+                format!("\"{}\"", t.text),
+                window.as_ref().map(|w| w.precision.as_str()).unwrap_or("unparsed"),
+                window.as_ref().map(format_window).unwrap_or("-".to_string()),
+                example
+            );
+        }
+
+        println!();
+        println!("{} of {} distinct release dates couldn't be parsed.", unparsed, texts.len());
+    }
+}
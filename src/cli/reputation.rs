@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use clap::Parser;
+
+use crate::config;
+use crate::db;
+use crate::db::repo::{ReleaseUpdateHandling, Repo, WishlistHandling};
+use crate::reputation::{adjusted_estimate, rank_studios, StudioReputation};
+
+#[derive(Debug, Parser)]
+pub struct RunReputation {
+    #[arg(short, long)]
+    pub config_file: Option<PathBuf>,
+    #[arg(short, long, default_value_t = 20, help = "Number of studios to show")]
+    pub limit: usize,
+}
+
+/// Describe a slip in the release date to the nearest day, e.g. "+45d"
+fn format_slip(slip: TimeDelta) -> String {
+    if slip.is_zero() { "-".to_string() } else { format!("+{}d", slip.num_days()) }
+}
+
+fn format_date(date: Option<DateTime<Utc>>) -> String {
+    date.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or("-".to_string())
+}
+
+fn truncate(s: &str, width: usize) -> String {
+    if s.chars().count() <= width {
+        s.to_string()
+    } else {
+        format!("{}…", s.chars().take(width - 1).collect::<String>())
+    }
+}
+
+impl RunReputation {
+    /// Rank developers and publishers by how often and how far their release dates have slipped,
+    /// then show the upcoming wishlist in light of the studios behind each game
+    pub async fn run(&self) {
+        let conf = config::read(self.config_file.as_ref());
+        let mut db_client = db::connect(&conf.db.connection_string()).await;
+        db::migrate(&mut db_client).await;
+
+        let repo = Repo::new(db_client);
+
+        let updates = match repo.get_studio_release_updates().await {
+            Ok(u) => u,
+            Err(e) => panic!("Error while reading release updates: {}", e),
+        };
+        let upcoming = match repo.get_upcoming_wishlist().await {
+            Ok(u) => u,
+            Err(e) => panic!("Error while reading the upcoming wishlist: {}", e),
+        };
+
+        let reputations = rank_studios(&updates);
+        let by_studio: HashMap<(&str, &str), &StudioReputation> = {
+            reputations.iter().map(|r| ((r.studio.as_str(), r.role.as_str()), r)).collect()
+        };
+
+        println!("Studios:");
+        println!(
+            "{:<30}  {:<9}  {:>5}  {:>7}  {:>6}  {:>6}  {:>8}",
+            "Studio", "Role", "Games", "Delayed", "Delays", "Avg", "Expected"
+        );
+        for r in reputations.iter().take(self.limit) {
+            println!(
+                "{:<30}  {:<9}  {:>5}  {:>6.0}%  {:>6}  {:>6}  {:>8}",
+                truncate(&r.studio, 30),
+                r.role,
+                r.games,
+                r.delay_rate() * 100.0,
+                r.delays,
+                format_slip(r.average_slip()),
+                format_slip(r.expected_slip())
+            );
+        }
+
+        println!();
+        println!("Upcoming wishlist:");
+        println!(
+            "{:<30}  {:<16}  {:<10}  {:<10}  {:>6}  Studios",
+            "Game", "Release date", "Estimate", "Adjusted", "Delays"
+        );
+        for game in &upcoming {
+            let studios: Vec<&StudioReputation> = {
+                game.developers
+                    .iter()
+                    .map(|d| (d.as_str(), "developer"))
+                    .chain(game.publishers.iter().map(|p| (p.as_str(), "publisher")))
+                    .filter_map(|key| by_studio.get(&key).copied())
+                    .collect()
+            };
+            let names: Vec<String> = {
+                game.developers
+                    .iter()
+                    .chain(game.publishers.iter().filter(|p| !game.developers.contains(p)))
+                    .map(|name| {
+                        let delays: usize = {
+                            studios.iter().filter(|s| &s.studio == name).map(|s| s.delays).max().unwrap_or(0)
+                        };
+                        if delays > 0 { format!("{} ({} delays)", name, delays) } else { name.clone() }
+                    })
+                    .collect()
+            };
+
+            println!(
+                "{:<30}  {:<16}  {:<10}  {:<10}  {:>6}  {}",
+                truncate(game.name.as_deref().unwrap_or("?"), 30),
+                truncate(game.release_date.as_deref().unwrap_or("-"), 16),
+                format_date(game.release_estimate),
+                format_date(adjusted_estimate(game.release_estimate, &studios)),
+                game.delays,
+                names.join(", ")
+            );
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use clap::Parser;
+
+use crate::config;
+use crate::db;
+use crate::db::repo::{Repo, SyncRunHandling};
+use crate::models::sync::{SyncPhase, SyncStatus};
+
+#[derive(Debug, Parser)]
+pub struct RunStatus {
+    #[arg(short, long)]
+    pub config_file: Option<PathBuf>,
+    #[arg(short, long, default_value_t = 5, help = "Number of recent sync runs to show")]
+    pub limit: i64,
+}
+
+/// Describe how long ago something happened, e.g. "3h 12m ago"
+fn format_age(t: &DateTime<Utc>, now: &DateTime<Utc>) -> String {
+    let mins = (*now - *t).num_minutes().max(0);
+
+    match mins {
+        m if m < 60 => format!("{}m ago", m),
+        m if m < 60 * 24 => format!("{}h {}m ago", m / 60, m % 60),
+        m => format!("{}d {}h ago", m / (60 * 24), (m / 60) % 24),
+    }
+}
+
+fn format_status(status: &SyncStatus) -> &'static str {
+    match status {
+        SyncStatus::Running => "⏳ running",
+        SyncStatus::Succeeded => "✅ ok",
+        SyncStatus::Failed => "❌ failed",
+    }
+}
+
+impl RunStatus {
+    /// Show recent sync runs and when each dataset was last synced successfully
+    pub async fn run(&self) {
+        let conf = config::read(self.config_file.as_ref());
+        let mut db_client = db::connect(&conf.db.connection_string()).await;
+        db::migrate(&mut db_client).await;
+
+        let repo = Repo::new(db_client);
+        let now = Utc::now();
+
+        let runs = match repo.get_recent_sync_runs(self.limit).await {
+            Ok(r) => r,
+            Err(e) => panic!("Error while reading sync history: {}", e),
+        };
+        let last_synced = match repo.get_last_successful_phases().await {
+            Ok(l) => l,
+            Err(e) => panic!("Error while reading sync history: {}", e),
+        };
+
+        if runs.is_empty() {
+            println!("No syncs have been recorded yet.");
+            return;
+        }
+
+        println!("Recent syncs:");
+        for run in &runs {
+            let duration = {
+                run.finished
+                    .map(|f| format!("{}s", (f - run.started).num_seconds()))
+                    .unwrap_or("-".to_string())
+            };
+
+            println!(
+                "  #{:<6} {}  {:<12} {:>6}  ({})",
+                run.id,
+                run.started.format("%Y-%m-%d %H:%M"),
+                format_status(&run.status),
+                duration,
+                format_age(&run.started, &now)
+            );
+
+            for p in &run.phases {
+                let count = p.item_count.map(|c| c.to_string()).unwrap_or("-".to_string());
+                println!(
+                    "      {:<14} {:<12} {:>6} items {:>6}s",
+                    p.phase.as_str(),
+                    format_status(&p.status),
+                    count,
+                    (p.finished - p.started).num_seconds()
+                );
+                if let Some(e) = &p.error {
+                    println!("        {}", e);
+                }
+            }
+            if let (Some(e), true) = (&run.error, run.phases.is_empty()) {
+                println!("        {}", e);
+            }
+        }
+
+        println!();
+        println!("Last successful sync by dataset:");
+        for phase in SyncPhase::ALL {
+            let age = {
+                last_synced
+                    .get(&phase)
+                    .map(|t| format_age(t, &now))
+                    .unwrap_or("never".to_string())
+            };
+            println!("  {:<14} {}", phase.as_str(), age);
+        }
+    }
+}
@@ -1,42 +1,62 @@
+use chrono::TimeDelta;
 use clap::Parser;
 
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use crate::config;
 use crate::db;
 use crate::db::repo::{Repo, SteamGamesHandling};
 use crate::db::sync::{Sync, SyncEvent};
-use crate::notion::NotionGamesRepo;
+use crate::feed;
+use crate::notify;
+use crate::notion::{DryRunNotion, NotionGamesRepo, NotionHandling};
 use crate::models::game::GameId;
-use crate::steam::SteamClient;
+use crate::models::sync::SyncPhase;
+use crate::steam::{DetailsFailure, SteamClient, StoreLimits};
 
 #[derive(Debug, Parser)]
 pub struct RunSync {
     #[arg(short, long)]
     config_file: Option<PathBuf>,
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_parser = parse_phase,
+        conflicts_with = "skip",
+        help = "Only run these phases, e.g. playtime,notion"
+    )]
+    only: Vec<SyncPhase>,
+    #[arg(long, value_delimiter = ',', value_parser = parse_phase, help = "Skip these phases, e.g. app-list")]
+    skip: Vec<SyncPhase>,
+    #[arg(long, help = "Print what the sync finds without saving anything or updating notion")]
+    dry_run: bool,
 }
 
-pub struct SyncReport {
-    repo: Repo,
+fn parse_phase(s: &str) -> Result<SyncPhase, String> {
+    SyncPhase::try_from(s)
+}
+
+pub struct SyncReport<'a> {
+    repo: &'a Repo,
 }
 
 /// Report interesting events collected during the sync process
-impl SyncReport {
-    fn new(repo: Repo) -> SyncReport {
-        SyncReport { repo: repo }
+impl SyncReport<'_> {
+    pub(super) fn new(repo: &Repo) -> SyncReport<'_> {
+        SyncReport { repo }
     }
 
-    async fn fetch_game_names(&self, events: &[SyncEvent]) -> HashMap<GameId, String> {
+    async fn fetch_game_names(
+        &self,
+        events: &[SyncEvent],
+        skipped: &[&[(GameId, DetailsFailure)]]
+    ) -> HashMap<GameId, String> {
         let ids: Vec<GameId> = {
             events.iter()
-                .map(|e| {
-                    match e {
-                        SyncEvent::ReleaseDateUpdated { game, .. } => Some(game.clone()),
-                        SyncEvent::Released { game } => Some(game.clone()),
-                    }
-                })
-                .flatten()
+                .map(|e| *e.game())
+                .chain(skipped.iter().flat_map(|s| s.iter().map(|(id, _)| *id)))
                 .collect()
         };
 
@@ -49,54 +69,110 @@ impl SyncReport {
         }
     }
 
-    async fn run(&self, events: &[SyncEvent]) -> () {
+    /// List games which were skipped for what, e.g. "details", and whether they'll be retried
+    fn report_skipped(what: &str, skipped: &[(GameId, DetailsFailure)], names_by_id: &HashMap<GameId, String>) {
+        if skipped.is_empty() {
+            return;
+        }
+
+        println!();
+        println!("⚠️  Skipped {} for {} games:", what, skipped.len());
+
+        for (id, failure) in skipped {
+            let name = names_by_id.get(id).map(|s| s.to_string()).unwrap_or(format!("{}", id));
+
+            match failure {
+                DetailsFailure::Transient(reason) => {
+                    println!("  {} (will retry next sync): {}", &name, &reason)
+                },
+                DetailsFailure::Permanent(reason) => {
+                    println!("  {}: {}", &name, &reason)
+                },
+            }
+        }
+    }
+
+    pub(super) async fn run(
+        &self,
+        events: &[SyncEvent],
+        skipped_details: &[(GameId, DetailsFailure)],
+        skipped_prices: &[(GameId, DetailsFailure)]
+    ) -> () {
         println!("");
         println!("============================================================");
         println!("=== SYNC REPORT                                          ===");
         println!("============================================================");
 
-        if events.is_empty() {
+        if events.is_empty() && skipped_details.is_empty() && skipped_prices.is_empty() {
             println!("Nothing significant to report.");
             println!("");
             return;
         }
 
-        let names_by_id = self.fetch_game_names(events).await;
+        let names_by_id = self.fetch_game_names(events, &[skipped_details, skipped_prices]).await;
 
         for e in events {
             // N.B. I don't use Display because this needs to be updated to first resolve app_id
             // by running a query, anyway; Display won't have sufficient context.
-            match e {
-                SyncEvent::ReleaseDateUpdated { game, prev_text, new_text, .. } => {
-                    let name = {
-                        names_by_id
-                            .get(&game)
-                            .map(|s| s.to_string())
-                            .unwrap_or(format!("{}", &game))
-                    };
-
-                    println!(
-                        "🔎 Release date changed for {}: \"{}\" -> \"{}\"",
-                        &name, &prev_text, &new_text
-                    )
-                },
-                SyncEvent::Released { game } => {
-                    // TODO: DRY
-                    let name = {
-                        names_by_id
-                            .get(&game)
-                            .map(|s| s.to_string())
-                            .unwrap_or(format!("{}", &game))
-                    };
-                    println!("🚀 {} is newly released!", &name)
-                },
-            }
+            let name = {
+                names_by_id
+                    .get(e.game())
+                    .map(|s| s.to_string())
+                    .unwrap_or(format!("{}", e.game()))
+            };
+
+            println!("{}", e.describe(&name));
         }
 
+        Self::report_skipped("details", skipped_details, &names_by_id);
+        Self::report_skipped("prices", skipped_prices, &names_by_id);
+
         println!("");
     }
 }
 
+/// Set up a sync against the steam and notion accounts given in config
+///
+/// For a dry run, changes to notion are only printed rather than made.
+pub(super) fn new_sync(conf: &config::Config, repo: Repo, dry_run: bool) -> Sync {
+    let store_limits = StoreLimits {
+        requests_per_window: conf.steam.store_requests_per_window,
+        window: Duration::from_secs(conf.steam.store_window_secs),
+        concurrency: conf.steam.store_concurrency,
+        ..StoreLimits::default()
+    };
+    let steam_client = SteamClient::new(
+        &conf.steam.api_key,
+        &conf.steam.api_hoststring,
+        &conf.steam.store_hoststring
+    ).with_store_limits(store_limits);
+    let notion = NotionGamesRepo::new(
+        &conf.notion.api_key,
+        &conf.notion.database_id,
+        &conf.notion.api_hoststring
+    );
+
+    let notion: Box<dyn NotionHandling> = {
+        if dry_run { Box::new(DryRunNotion::new(notion)) } else { Box::new(notion) }
+    };
+
+    Sync::new(&conf.steam.user_id, repo, Box::new(steam_client), notion)
+}
+
+/// Pass on what a sync found: deliver events to notifiers and rewrite the feed, if configured
+pub(super) async fn publish(conf: &config::Config, repo: &Repo) {
+    let notifiers = notify::from_config(&conf.notify);
+    notify::dispatch(repo, &notifiers, TimeDelta::days(conf.notify.lookback_days)).await;
+
+    if let Some(f) = &conf.feed {
+        let path = PathBuf::from(&f.path);
+        match feed::write_feed(repo, &path, &f.title, f.self_url.as_deref(), f.days).await {
+            Ok(count) => println!("Wrote {} entries to feed at {}", count, path.display()),
+            Err(e) => eprintln!("Failed to write feed: {}", e),
+        }
+    }
+}
+
 impl RunSync {
     /// Primary rusteam action: sync data from the official steam API and notion
     pub(super) async fn run(&self) {
@@ -105,27 +181,40 @@ impl RunSync {
         let mut db_client = db::connect(&conf.db.connection_string()).await;
         db::migrate(&mut db_client).await;
 
-        let repo = Repo::new(db_client);
-        let steam_client = SteamClient::new(
-            &conf.steam.api_key,
-            &conf.steam.api_hoststring,
-            &conf.steam.store_hoststring
-        );
-        let notion = NotionGamesRepo::new(
-            &conf.notion.api_key,
-            &conf.notion.database_id,
-            &conf.notion.api_hoststring
-        );
-
-        let mut sync = Sync::new(
-            &conf.steam.user_id, repo, Box::new(steam_client), Box::new(notion)
-        );
-
-        let mut events = sync.sync_steam().await.unwrap();
-        events.extend(sync.sync_notion().await.unwrap());
-
-        let report = SyncReport::new(sync.repo);
-        report.run(&events).await;
+        let phases: Vec<SyncPhase> = {
+            SyncPhase::ALL
+                .into_iter()
+                .filter(|p| self.only.is_empty() || self.only.contains(p))
+                .filter(|p| !self.skip.contains(p))
+                .collect()
+        };
+
+        let mut sync = new_sync(&conf, Repo::new(db_client), self.dry_run);
+
+        // Everything the dry run writes to postgres is rolled back once it's been reported on
+        if self.dry_run {
+            println!("Dry run: nothing will be saved, and notion won't be updated");
+            sync.repo.begin_transaction().await.expect("Failed to start dry run transaction");
+        }
+
+        let res = sync.run_phases(&phases).await;
+
+        // Events from phases which finished before any failure are still worth delivering
+        if !self.dry_run {
+            publish(&conf, &sync.repo).await;
+        }
+
+        if let Ok(events) = &res {
+            let report = SyncReport::new(&sync.repo);
+            report.run(events, &sync.skipped_details, &sync.skipped_prices).await;
+        }
+
+        if self.dry_run {
+            sync.repo.rollback_transaction().await.expect("Failed to roll back dry run");
+        }
+
+        if let Err(e) = res {
+            panic!("Sync failed: {}", e);
+        }
     }
 }
-
@@ -0,0 +1,50 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use crate::config;
+use crate::db;
+use crate::db::repo::{Repo, TargetPriceHandling};
+use crate::models::game::{format_price, parse_price, GameId};
+
+#[derive(Debug, Parser)]
+pub struct RunTargetPrice {
+    #[arg(short, long)]
+    pub config_file: Option<PathBuf>,
+    #[arg(short, long, help = "App ID of the game")]
+    pub game: String,
+    #[arg(
+      short, long, required_unless_present = "clear",
+      help = "Price in pounds at which to alert, e.g. 9.99"
+    )]
+    pub price: Option<String>,
+    #[arg(long, conflicts_with = "price", help = "Remove the target price for this game")]
+    pub clear: bool,
+}
+
+impl RunTargetPrice {
+    pub async fn run(&self) {
+        let conf = config::read(self.config_file.as_ref());
+        let mut db_client = db::connect(&conf.db.connection_string()).await;
+        db::migrate(&mut db_client).await;
+
+        let repo = Repo::new(db_client);
+        let id = GameId::try_from(self.game.as_ref()).unwrap();
+
+        if self.clear {
+            println!("Removing target price for game {}...", &id);
+            if let Err(e) = repo.delete_target_price(&id).await {
+                panic!("Error while removing target price: {}", e)
+            }
+            return;
+        }
+
+        let raw_price = self.price.as_deref().unwrap_or_default();
+        let price = parse_price(raw_price).expect("Invalid price, expected pounds like 9.99");
+
+        println!("Setting target price for game {} to {}...", &id, format_price(price));
+        if let Err(e) = repo.set_target_price(&id, price).await {
+            panic!("Error while setting target price: {}", e)
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::path::Path;
 
 use home::home_dir;
@@ -10,7 +11,12 @@ pub struct Config {
     #[serde(default = "default_db")]
     pub db: Db,
     pub steam: Steam,
-    pub notion: Notion
+    pub notion: Notion,
+    #[serde(default)]
+    pub notify: Notify,
+    pub feed: Option<Feed>,
+    #[serde(default)]
+    pub daemon: Daemon,
 }
 
 #[serde_inline_default]
@@ -60,6 +66,13 @@ pub struct Steam {
     #[serde_inline_default("https://store.steampowered.com".to_string())]
     pub store_hoststring: String,
     pub user_id: String,
+    // The store API throttles at around 200 requests per 5 minutes
+    #[serde_inline_default(200)]
+    pub store_requests_per_window: u32,
+    #[serde_inline_default(300)]
+    pub store_window_secs: u64,
+    #[serde_inline_default(4)]
+    pub store_concurrency: usize,
 }
 
 #[serde_inline_default]
@@ -71,6 +84,117 @@ pub struct Notion {
     pub api_hoststring: String,
 }
 
+#[serde_inline_default]
+#[derive(Deserialize, Debug)]
+pub struct Notify {
+    // How far back to look for events a notifier hasn't delivered yet, e.g. after an outage
+    #[serde_inline_default(7)]
+    pub lookback_days: i64,
+    #[serde(default)]
+    pub webhook: Vec<Webhook>,
+    #[serde(default)]
+    pub discord: Vec<ChatWebhook>,
+    #[serde(default)]
+    pub slack: Vec<ChatWebhook>,
+    #[serde(default)]
+    pub email: Vec<Email>,
+}
+
+impl Default for Notify {
+    fn default() -> Self {
+        Notify { lookback_days: 7, webhook: vec![], discord: vec![], slack: vec![], email: vec![] }
+    }
+}
+
+#[serde_inline_default]
+#[derive(Deserialize, Debug)]
+pub struct Webhook {
+    // Used to track which events have been delivered, so should be unique and kept stable
+    #[serde_inline_default("webhook".to_string())]
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    // Kinds of event to send, e.g. "released" or "release_date_updated"; all of them if unset
+    pub events: Option<Vec<String>>,
+}
+
+/// An incoming webhook for a chat service, i.e. discord or slack
+#[derive(Deserialize, Debug)]
+pub struct ChatWebhook {
+    // Used to track which events have been delivered, so should be unique and kept stable
+    pub name: String,
+    pub webhook_url: String,
+    // Kinds of event to send, e.g. "released" or "release_date_updated"; all of them if unset
+    pub events: Option<Vec<String>>,
+}
+
+/// An Atom feed of release news, rewritten after every sync
+#[serde_inline_default]
+#[derive(Deserialize, Debug)]
+pub struct Feed {
+    pub path: String,
+    #[serde_inline_default("rusteam release news".to_string())]
+    pub title: String,
+    // Public URL the feed is served from, which feed readers like to know
+    pub self_url: Option<String>,
+    #[serde_inline_default(90)]
+    pub days: i64,
+}
+
+/// Scheduling for `rusteam daemon`
+#[serde_inline_default]
+#[derive(Deserialize, Debug)]
+pub struct Daemon {
+    // How often to run each sync phase, keyed by phase name, e.g. played_games = 60; these
+    // override the defaults, and 0 stops a phase being run at all
+    #[serde(default)]
+    pub interval_minutes: HashMap<String, i64>,
+    // How soon to try a phase again after it fails, if that's sooner than its usual interval
+    #[serde_inline_default(15)]
+    pub retry_minutes: i64,
+}
+
+impl Default for Daemon {
+    fn default() -> Self {
+        Daemon { interval_minutes: HashMap::new(), retry_minutes: 15 }
+    }
+}
+
+/// How to secure the connection to an SMTP server
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    // Upgrade a plain connection with STARTTLS, usually on port 587
+    StartTls,
+    // Connect with TLS from the start, usually on port 465
+    Tls,
+    // No encryption at all; only suitable for a local relay
+    None,
+}
+
+#[serde_inline_default]
+#[derive(Deserialize, Debug)]
+pub struct Email {
+    // Used to track which events have been delivered, so should be unique and kept stable
+    #[serde_inline_default("email".to_string())]
+    pub name: String,
+    pub smtp_host: String,
+    #[serde_inline_default(587)]
+    pub smtp_port: u16,
+    #[serde_inline_default(SmtpSecurity::StartTls)]
+    pub security: SmtpSecurity,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    pub to: Vec<String>,
+    #[serde_inline_default("rusteam digest".to_string())]
+    pub subject: String,
+    // Send at most one digest in this period, e.g. 168 for a weekly summary
+    pub min_interval_hours: Option<i64>,
+    // Kinds of event to send, e.g. "released" or "release_date_updated"; all of them if unset
+    pub events: Option<Vec<String>>,
+}
 
 pub fn read<P: AsRef<Path>>(path: Option<&P>) -> Config {
     let f = match path {
@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use thiserror::Error;
+
+use crate::config;
+use crate::models::sync::SyncPhase;
+
+#[derive(Debug, Error)]
+pub enum DaemonError {
+    #[error("Unknown sync phase in daemon config: {0}")]
+    UnknownPhase(String),
+    #[error("Negative interval for {0} in daemon config")]
+    NegativeInterval(String),
+}
+
+pub type Result<T> = std::result::Result<T, DaemonError>;
+
+/// How often each phase runs unless configured otherwise, in minutes
+///
+/// The app list is huge and barely changes, whereas playtime is cheap to check and changes
+/// whenever a game is played.
+pub fn default_interval_minutes(phase: SyncPhase) -> i64 {
+    match phase {
+        SyncPhase::SteamGames => 24 * 60,
+        SyncPhase::OwnedGames => 6 * 60,
+        SyncPhase::Wishlist => 60,
+        SyncPhase::GameDetails => 3 * 60,
+        SyncPhase::Prices => 3 * 60,
+        SyncPhase::PlayedGames => 60,
+        SyncPhase::Notion => 60,
+    }
+}
+
+/// Keeps track of when each sync phase is next due to run
+#[derive(Debug)]
+pub struct Schedule {
+    intervals: HashMap<SyncPhase, TimeDelta>,
+    retry: TimeDelta,
+    next: HashMap<SyncPhase, DateTime<Utc>>,
+}
+
+impl Schedule {
+    /// Schedule each phase an interval after it last succeeded, or straight away if it never has
+    pub fn new(
+        intervals: HashMap<SyncPhase, TimeDelta>,
+        retry: TimeDelta,
+        last_synced: &HashMap<SyncPhase, DateTime<Utc>>,
+        now: &DateTime<Utc>
+    ) -> Schedule {
+        let next = {
+            intervals
+                .iter()
+                .map(|(phase, interval)| {
+                    let due = last_synced.get(phase).map(|t| *t + *interval).unwrap_or(*now);
+                    (*phase, due)
+                })
+                .collect()
+        };
+
+        Schedule { intervals, retry, next }
+    }
+
+    /// Build a schedule from the [daemon] config, on top of the default intervals
+    pub fn from_config(
+        conf: &config::Daemon,
+        last_synced: &HashMap<SyncPhase, DateTime<Utc>>,
+        now: &DateTime<Utc>
+    ) -> Result<Schedule> {
+        let mut minutes: HashMap<SyncPhase, i64> = {
+            SyncPhase::ALL.into_iter().map(|p| (p, default_interval_minutes(p))).collect()
+        };
+
+        for (name, m) in &conf.interval_minutes {
+            let phase = {
+                SyncPhase::try_from(name.as_str())
+                    .map_err(|_| DaemonError::UnknownPhase(name.to_string()))?
+            };
+            if *m < 0 {
+                return Err(DaemonError::NegativeInterval(name.to_string()));
+            }
+            minutes.insert(phase, *m);
+        }
+
+        let intervals = {
+            minutes
+                .into_iter()
+                .filter(|(_, m)| *m > 0)
+                .map(|(p, m)| (p, TimeDelta::minutes(m)))
+                .collect()
+        };
+
+        Ok(Schedule::new(intervals, TimeDelta::minutes(conf.retry_minutes), last_synced, now))
+    }
+
+    /// Phases which are due to run, in the order a full sync would run them
+    pub fn due(&self, now: &DateTime<Utc>) -> Vec<SyncPhase> {
+        SyncPhase::ALL
+            .into_iter()
+            .filter(|p| self.next.get(p).is_some_and(|t| t <= now))
+            .collect()
+    }
+
+    /// When the next phase is due, if any are scheduled at all
+    pub fn next_due(&self) -> Option<DateTime<Utc>> {
+        self.next.values().min().cloned()
+    }
+
+    /// Reschedule a phase after running it; failed phases are retried sooner than usual
+    pub fn record(&mut self, phase: SyncPhase, succeeded: bool, now: &DateTime<Utc>) {
+        let Some(interval) = self.intervals.get(&phase) else { return };
+
+        let wait = if succeeded { *interval } else { (*interval).min(self.retry) };
+        self.next.insert(phase, *now + wait);
+    }
+}
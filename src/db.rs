@@ -19,12 +19,20 @@ pub async fn migrate(db_client: &mut Client) -> () {
 
 /// Connect to postgres and report any connection errors as async
 pub async fn connect(connection_string: &str) -> Client {
-    let (db_client, conn) = tokio_postgres::connect(connection_string, NoTls).await.unwrap();
+    try_connect(connection_string).await.unwrap()
+}
+
+/// Connect to postgres, giving up with an error if it can't be reached
+///
+/// Once connected, losing the connection is only logged, leaving the client closed; see
+/// `Client::is_closed` for noticing this and connecting again.
+pub async fn try_connect(connection_string: &str) -> Result<Client, tokio_postgres::Error> {
+    let (db_client, conn) = tokio_postgres::connect(connection_string, NoTls).await?;
     tokio::spawn(async move {
         if let Err(e) = conn.await {
-            panic!("connection error: {}", e);
+            eprintln!("connection error: {}", e);
         }
     });
 
-    db_client
+    Ok(db_client)
 }
@@ -1,13 +1,18 @@
 use std::collections::{HashMap, HashSet};
+use std::future::Future;
 use std::num::TryFromIntError;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use itertools::Itertools;
 use thiserror::Error;
-use tokio_postgres::{Client, Error as PgError};
+use tokio_postgres::{Client, Error as PgError, Row};
+use tokio_postgres::types::Json;
 
+use crate::db::sync::{RecordedEvent, SyncEvent};
 use crate::models::game::*;
+use crate::models::sync::*;
 
 #[derive(Error, Debug)]
 pub enum RepoError {
@@ -20,13 +25,55 @@ pub enum RepoError {
 pub type Result<T> = std::result::Result<T, RepoError>;
 
 pub struct Repo {
-    db: Client
+    db: Client,
+    /// Whether a transaction was started with `begin_transaction`
+    in_transaction: AtomicBool,
 }
 
 // FIXME: Fix inconsistent naming of app_id, appid, id, game_id, etc.
 impl Repo {
     pub fn new(db: Client) -> Repo {
-        Repo { db: db }
+        Repo { db: db, in_transaction: AtomicBool::new(false) }
+    }
+
+    /// Whether the connection to postgres has been lost, so every query will fail
+    pub fn is_closed(&self) -> bool {
+        self.db.is_closed()
+    }
+
+    /// Start a transaction which everything the repo does afterwards will be part of
+    ///
+    /// All queries go over the repo's one connection, so this can wrap a whole sync; used for
+    /// dry runs, which are rolled back at the end.
+    pub async fn begin_transaction(&self) -> Result<()> {
+        self.db.batch_execute("BEGIN").await?;
+        self.in_transaction.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Discard everything done since `begin_transaction`
+    pub async fn rollback_transaction(&self) -> Result<()> {
+        self.in_transaction.store(false, Ordering::SeqCst);
+        self.db.batch_execute("ROLLBACK").await?;
+        Ok(())
+    }
+
+    /// Run a statement whose failure is only logged, without it aborting the transaction the
+    /// repo is in, if any. Otherwise every query after it would fail too, unlike outside one.
+    async fn tolerate<T>(
+        &self,
+        statement: impl Future<Output = std::result::Result<T, PgError>>
+    ) -> std::result::Result<T, PgError> {
+        if !self.in_transaction.load(Ordering::SeqCst) {
+            return statement.await;
+        }
+
+        self.db.batch_execute("SAVEPOINT tolerated").await?;
+        let res = statement.await;
+
+        let end = if res.is_ok() { "RELEASE SAVEPOINT tolerated" } else { "ROLLBACK TO SAVEPOINT tolerated" };
+        self.db.batch_execute(end).await?;
+        res
     }
 
     async fn get_unknown_app_ids(&self, app_ids: &HashSet<u32>) -> Result<HashSet<u32>> {
@@ -73,17 +120,33 @@ impl Repo {
 pub trait SteamGamesHandling {
     async fn insert_steam_games<T: AsRef<str>>(&self, games: HashMap<u32, T>) -> Result<()>;
     async fn get_game_names_by_id(&self, ids: &[GameId]) -> Result<HashMap<GameId, String>>;
+    async fn get_all_game_names(&self) -> Result<Vec<(GameId, String)>>;
+    async fn upsert_steam_apps(&self, apps: &[SteamApp]) -> Result<u64>;
+    async fn get_app_list_marks(&self) -> Result<HashMap<AppType, DateTime<Utc>>>;
+    async fn set_app_list_mark(&self, app_type: AppType, last_modified: &DateTime<Utc>) -> Result<()>;
+    async fn get_tracked_games_missing_names(&self) -> Result<Vec<GameId>>;
+    async fn insert_missing_names(&self, names: &[(GameId, String)]) -> Result<u64>;
 }
 
 pub trait OwnedGamesHandling {
     async fn insert_owned_games(&self, games: &[GameId]) -> Result<()>;
+    async fn get_upcoming_owned_game_ids(&self) -> Result<Vec<GameId>>;
 }
 
 pub trait GameDetailsHandling {
     async fn get_games_missing_details(&self) -> Result<Vec<GameId>>;
     async fn insert_game_details(&self, details: &[GameDetails]) -> Result<()>;
-    async fn mark_game_detail_failures(&self, games: &[GameId]) -> ();
+    async fn update_game_metadata(&self, details: &[GameDetails]) -> Result<()>;
+    async fn mark_game_detail_failures(&self, games: &[GameId], failed: &DateTime<Utc>) -> ();
     async fn get_release_dates(&self, games: &[&GameId]) -> Result<HashMap<GameId, String>>;
+    async fn get_release_date_texts(&self) -> Result<Vec<ReleaseDateText>>;
+    async fn get_unreleased_games(&self, games: &[&GameId]) -> Result<HashSet<GameId>>;
+}
+
+pub trait GameDetailsBlacklistHandling {
+    async fn get_blacklist(&self) -> Result<Vec<BlacklistedGame>>;
+    async fn delete_blacklist_entries(&self, games: &[GameId]) -> Result<u64>;
+    async fn clear_blacklist(&self) -> Result<u64>;
 }
 
 pub trait PlayedGamesHandling {
@@ -95,11 +158,26 @@ pub trait NotedGamesHandling {
     async fn get_appids_by_name<T: AsRef<str>>(&self, names: &[T]) -> Result<HashMap<String, GameId>>;
     async fn get_upcoming_noted_game_ids(&self) -> Result<Vec<GameId>>;
     async fn get_newly_released_games(&self) -> Result<Vec<ReleasedGame>>;
+    async fn set_noted_game_app_id(&self, note_id: &str, app_id: &GameId) -> Result<()>;
+}
+
+pub trait AppIdCandidateHandling {
+    async fn get_rejected_candidates(&self) -> Result<HashSet<(String, GameId)>>;
+    async fn insert_app_id_candidates(&self, candidates: &[AppIdCandidate]) -> Result<()>;
+    async fn get_pending_candidates(&self) -> Result<Vec<(i32, AppIdCandidate)>>;
+    async fn get_candidate(&self, id: i32) -> Result<Option<AppIdCandidate>>;
+    async fn resolve_candidate(&self, id: i32, status: &CandidateStatus) -> Result<()>;
+}
+
+pub trait GameTagHandling {
+    async fn update_game_tags(&self, notes: &[NotedGame]) -> Result<()>;
 }
 
 pub trait WishlistHandling {
+    async fn get_wishlisted_ids(&self) -> Result<HashSet<GameId>>;
     async fn update_wishlist(&self, items: &[WishlistedGame]) -> Result<()>;
     async fn get_upcoming_wishlisted_game_ids(&self) -> Result<Vec<GameId>>;
+    async fn get_upcoming_wishlist(&self) -> Result<Vec<UpcomingGame>>;
 }
 
 pub trait ReleaseUpdateHandling {
@@ -109,8 +187,53 @@ pub trait ReleaseUpdateHandling {
         prev_text: &str,
         prev_date: &Option<DateTime<Utc>>,
         new_text: &str,
-        new_date: &Option<DateTime<Utc>>
+        new_date: &Option<DateTime<Utc>>,
+        change: ReleaseChange
+    ) -> Result<()>;
+    async fn get_release_updates_since(&self, since: &DateTime<Utc>) -> Result<Vec<ReleaseUpdate>>;
+    async fn get_unclassified_release_updates(&self) -> Result<Vec<(i32, String, String)>>;
+    async fn set_release_update_changes(&self, changes: &[(i32, ReleaseChange)]) -> Result<u64>;
+    async fn get_studio_release_updates(&self) -> Result<Vec<StudioReleaseUpdate>>;
+}
+
+pub trait GamePriceHandling {
+    async fn get_price_tracked_game_ids(&self) -> Result<Vec<GameId>>;
+    async fn get_price_history(&self, games: &[GameId]) -> Result<HashMap<GameId, PriceHistory>>;
+    async fn insert_game_prices(&self, prices: &[GamePrice]) -> Result<()>;
+}
+
+pub trait TargetPriceHandling {
+    async fn get_target_prices(&self) -> Result<HashMap<GameId, u32>>;
+    async fn set_target_price(&self, game: &GameId, price: u32) -> Result<()>;
+    async fn delete_target_price(&self, game: &GameId) -> Result<()>;
+}
+
+pub trait SyncRunHandling {
+    async fn start_sync_run(&self, started: &DateTime<Utc>) -> Result<i32>;
+    async fn finish_sync_run(
+        &self,
+        id: i32,
+        finished: &DateTime<Utc>,
+        status: &SyncStatus,
+        error: Option<&str>
     ) -> Result<()>;
+    async fn insert_sync_phase(&self, run_id: i32, phase: &SyncPhaseRun) -> Result<()>;
+    async fn get_recent_sync_runs(&self, limit: i64) -> Result<Vec<SyncRun>>;
+    async fn get_last_successful_phases(&self) -> Result<HashMap<SyncPhase, DateTime<Utc>>>;
+}
+
+pub trait SyncEventHandling {
+    async fn insert_sync_events(&self, run_id: Option<i32>, events: &[SyncEvent]) -> Result<()>;
+    async fn get_sync_events_since(&self, since: &DateTime<Utc>) -> Result<Vec<RecordedEvent>>;
+    async fn get_undelivered_events(
+        &self,
+        notifier: &str,
+        since: &DateTime<Utc>
+    ) -> Result<Vec<RecordedEvent>>;
+    async fn mark_events_delivered(&self, notifier: &str, event_ids: &[i64]) -> Result<()>;
+    async fn mark_events_skipped(&self, notifier: &str, event_ids: &[i64]) -> Result<()>;
+    async fn get_last_delivery(&self, notifier: &str) -> Result<Option<DateTime<Utc>>>;
+    async fn get_games_with_event(&self, kind: &str, games: &[GameId]) -> Result<HashSet<GameId>>;
 }
 
 pub trait IgnoredGamesHandling {
@@ -149,39 +272,184 @@ impl SteamGamesHandling for Repo {
                 .collect()
         )
     }
+
+    async fn get_all_game_names(&self) -> Result<Vec<(GameId, String)>> {
+        let q = r#"SELECT app_id, name FROM steam_game WHERE name IS NOT NULL"#;
+
+        Ok(
+            self.db
+                .query(q, &[])
+                .await?
+                .into_iter()
+                .map(|row| (GameId::from(row.get::<usize, i64>(0)), row.get(1)))
+                .collect()
+        )
+    }
+
+    /// Add apps from the steam catalogue, updating the name and type of any already known
+    async fn upsert_steam_apps(&self, apps: &[SteamApp]) -> Result<u64> {
+        let q = r#"
+            INSERT INTO steam_game (app_id, name, app_type, last_modified)
+            SELECT * FROM UNNEST($1::BIGINT[], $2::TEXT[], $3::TEXT[], $4::TIMESTAMP[])
+            ON CONFLICT (app_id) DO UPDATE
+            SET name = EXCLUDED.name,
+                app_type = EXCLUDED.app_type,
+                last_modified = EXCLUDED.last_modified
+        "#;
+
+        // An app can only be updated once per statement, so keep the last we were given for each
+        let unique: HashMap<GameId, &SteamApp> = apps.iter().map(|a| (a.id, a)).collect();
+        let mut row_count: u64 = 0;
+
+        // The first sync brings in the whole catalogue, so don't try to send it all at once
+        for chunk in &unique.into_values().chunks(5000) {
+            let chunk: Vec<&SteamApp> = chunk.collect();
+            let ids: Vec<i64> = chunk.iter().map(|a| a.id.into()).collect();
+            let names: Vec<&str> = chunk.iter().map(|a| a.name.as_str()).collect();
+            let types: Vec<&str> = chunk.iter().map(|a| a.app_type.as_str()).collect();
+            let modified: Vec<NaiveDateTime> = chunk.iter().map(|a| a.last_modified.naive_utc()).collect();
+
+            row_count += self.db.execute(q, &[&ids, &names, &types, &modified]).await?;
+        }
+
+        println!("Inserted or updated {} apps in steam_game table", row_count);
+        Ok(row_count)
+    }
+
+    async fn get_app_list_marks(&self) -> Result<HashMap<AppType, DateTime<Utc>>> {
+        let q = "SELECT app_type, last_modified FROM app_list_sync";
+
+        let mut marks = HashMap::new();
+        for row in self.db.query(q, &[]).await? {
+            if let Ok(app_type) = AppType::try_from(row.get::<usize, &str>(0)) {
+                marks.insert(app_type, row.get::<usize, NaiveDateTime>(1).and_utc());
+            }
+        }
+        Ok(marks)
+    }
+
+    async fn set_app_list_mark(&self, app_type: AppType, last_modified: &DateTime<Utc>) -> Result<()> {
+        let q = r#"
+            INSERT INTO app_list_sync (app_type, last_modified) VALUES ($1, $2)
+            ON CONFLICT (app_type) DO UPDATE SET last_modified = EXCLUDED.last_modified
+        "#;
+
+        self.db.execute(q, &[&app_type.as_str(), &last_modified.naive_utc()]).await?;
+        Ok(())
+    }
+
+    /// Get owned, wishlisted and noted games which the app list hasn't given us a name for
+    async fn get_tracked_games_missing_names(&self) -> Result<Vec<GameId>> {
+        // Games steam won't give details for are left to the blacklist's backoff; otherwise we'd
+        // ask for them again every sync. Limited like get_games_missing_details to go easy on
+        // the store API.
+        let q = r#"
+            WITH tracked AS (
+                SELECT app_id FROM owned_game
+                UNION SELECT app_id FROM wishlist
+                UNION SELECT app_id FROM noted_game WHERE app_id IS NOT NULL
+            )
+            SELECT
+                tracked.app_id
+            FROM
+                tracked
+                LEFT JOIN steam_game sg ON tracked.app_id = sg.app_id
+                LEFT JOIN game_details_blacklist blacklist ON tracked.app_id = blacklist.app_id
+            WHERE
+                sg.name IS NULL AND
+                blacklist.app_id IS NULL
+            LIMIT
+                100
+        "#;
+
+        Ok(
+            self.db
+                .query(q, &[]).await?
+                .into_iter()
+                .map(|row| GameId::from(row.get::<usize, i64>(0)))
+                .collect()
+        )
+    }
+
+    /// Fill in names for games which steam_game has no name for; known names are left alone
+    async fn insert_missing_names(&self, names: &[(GameId, String)]) -> Result<u64> {
+        let q = r#"
+            INSERT INTO steam_game (app_id, name)
+            SELECT * FROM UNNEST($1::BIGINT[], $2::TEXT[])
+            ON CONFLICT (app_id) DO UPDATE
+            SET name = EXCLUDED.name
+            WHERE steam_game.name IS NULL
+        "#;
+
+        let unique: HashMap<GameId, &str> = names.iter().map(|(id, n)| (*id, n.as_str())).collect();
+        let ids: Vec<i64> = unique.keys().map(|id| (*id).into()).collect();
+        let names: Vec<&str> = unique.values().cloned().collect();
+
+        let count = self.db.execute(q, &[&ids, &names]).await?;
+        if count > 0 {
+            println!("Filled in {} missing names in steam_game table", count);
+        }
+        Ok(count)
+    }
 }
 
 impl GameDetailsHandling for Repo {
     /// Get games which are being tracked and are missing in the game_details table
     /// Tracked games means those owned or wishlisted
+    /// Also gets owned, wishlisted and noted games whose details were recorded before the current
+    /// version of their metadata, so that's backfilled for games which are never otherwise fetched
     async fn get_games_missing_details(&self) -> Result<Vec<GameId>> {
         // Limit results to 100 to avoid backfilling hundreds of games at once; we can catch up
         // 100 at a time over several syncs this way.
         // This is just a very basic way of avoiding hitting the Steam API rate limit
 
         // N.B. we also join to game_details_blacklist to avoid repeatedly scraping games for
-        // which steam doesn't return a well-formed definition
+        // which steam doesn't return a well-formed definition. Each failure doubles the time
+        // before we try again, and after too many we stop trying until the entry is reset.
         let q = r#"
             WITH tracked AS (SELECT app_id FROM owned_game UNION SELECT app_id FROM wishlist)
             SELECT
-                tracked.app_id
+                candidate.app_id
             FROM
-                tracked
-                LEFT JOIN game_details details ON tracked.app_id = details.app_id
-                LEFT JOIN game_details_blacklist blacklist ON tracked.app_id = blacklist.app_id
+                (
+                    SELECT tracked.app_id
+                    FROM tracked LEFT JOIN game_details details ON tracked.app_id = details.app_id
+                    WHERE details.app_id IS NULL
+                    UNION
+                    SELECT details.app_id
+                    FROM game_details details
+                    WHERE
+                        details.metadata_version < $4 AND
+                        (
+                            details.app_id IN (SELECT app_id FROM tracked) OR
+                            details.app_id IN (SELECT app_id FROM noted_game WHERE app_id IS NOT NULL)
+                        )
+                ) candidate
+                LEFT JOIN game_details_blacklist blacklist ON candidate.app_id = blacklist.app_id
             WHERE
-                details.app_id IS NULL AND
                 (
-                    blacklist.failure_count < 5 OR
-                    blacklist.failure_count IS NULL
+                    blacklist.app_id IS NULL OR
+                    (
+                        blacklist.failure_count < $1 AND
+                        (
+                            blacklist.last_failed IS NULL OR
+                            blacklist.last_failed +
+                                $2 * POWER(2, GREATEST(blacklist.failure_count - 1, 0)) * INTERVAL '1 hour'
+                                <= $3
+                        )
+                    )
                 )
             LIMIT
                 100
         "#;
 
+        let max_failures = i32::try_from(BLACKLIST_MAX_FAILURES)?;
+        let backoff_hours = f64::from(BLACKLIST_BACKOFF_HOURS);
+        let now = Utc::now().naive_utc();
+
         Ok(
             self.db
-                .query(q, &[]).await?
+                .query(q, &[&max_failures, &backoff_hours, &now, &GAME_METADATA_VERSION]).await?
                 .into_iter()
                 .map(|row| GameId::from(row.get::<usize, i64>(0)))
                 .collect()
@@ -192,11 +460,15 @@ impl GameDetailsHandling for Repo {
         let q = r#"
             INSERT INTO game_details (
                 app_id, description, controller_support, coop, local_coop, metacritic_percent,
-                is_released, release_date, release_estimate, recorded
+                is_released, release_date, release_estimate, recorded, app_type, is_free,
+                header_image, release_earliest, release_latest, release_precision
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
             ON CONFLICT (app_id) DO UPDATE
                 SET description = excluded.description,
+                    app_type = excluded.app_type,
+                    is_free = excluded.is_free,
+                    header_image = excluded.header_image,
                     controller_support = excluded.controller_support,
                     coop = excluded.coop,
                     local_coop = excluded.local_coop,
@@ -204,6 +476,11 @@ impl GameDetailsHandling for Repo {
                     is_released = excluded.is_released,
                     release_date = COALESCE(excluded.release_date, NULLIF(excluded.release_date, ''), game_details.release_date),
                     release_estimate = COALESCE(excluded.release_estimate, game_details.release_estimate),
+                    release_earliest = CASE WHEN excluded.release_precision IS NULL
+                        THEN game_details.release_earliest ELSE excluded.release_earliest END,
+                    release_latest = CASE WHEN excluded.release_precision IS NULL
+                        THEN game_details.release_latest ELSE excluded.release_latest END,
+                    release_precision = COALESCE(excluded.release_precision, game_details.release_precision),
                     recorded = excluded.recorded
         "#;
 
@@ -211,8 +488,8 @@ impl GameDetailsHandling for Repo {
 
         println!("Inserting {} game details into game_details table", details.len());
         for d in details {
-            let res = self.db
-                .execute(
+            let res = self.tolerate(
+                self.db.execute(
                     q,
                     &[
                         &Into::<i64>::into(d.id.clone()),
@@ -225,9 +502,15 @@ impl GameDetailsHandling for Repo {
                         &d.release_date,
                         &d.release_estimate.map(|r| r.naive_utc()),
                         &d.recorded.naive_utc(),
+                        &d.app_type,
+                        &d.is_free,
+                        &d.header_image,
+                        &d.release_window.as_ref().and_then(|w| w.earliest).map(|r| r.naive_utc()),
+                        &d.release_window.as_ref().and_then(|w| w.latest).map(|r| r.naive_utc()),
+                        &d.release_window.as_ref().map(|w| w.precision.as_str()),
                     ],
                 )
-                .await;
+            ).await;
 
             match res {
                 Ok(c) => row_count += c,
@@ -239,7 +522,69 @@ impl GameDetailsHandling for Repo {
         Ok(())
     }
 
-    async fn mark_game_detail_failures(&self, games: &[GameId]) -> () {
+    /// Replace the categories, genres, developers, publishers and platforms recorded for each game
+    async fn update_game_metadata(&self, details: &[GameDetails]) -> Result<()> {
+        let categories: HashMap<u32, &str> = {
+            details.iter().flat_map(|d| d.categories.iter().map(|c| (c.id, c.description.as_str()))).collect()
+        };
+        self.upsert_descriptions("category", &categories).await?;
+
+        let genres: HashMap<u32, &str> = {
+            details.iter().flat_map(|d| d.genres.iter().map(|g| (g.id, g.description.as_str()))).collect()
+        };
+        self.upsert_descriptions("genre", &genres).await?;
+
+        let names: HashSet<&str> = {
+            details
+                .iter()
+                .flat_map(|d| d.developers.iter().chain(d.publishers.iter()).map(|n| n.as_str()))
+                .collect()
+        };
+        let company_ids = self.upsert_companies(&names).await?;
+
+        let company_delete_q = r#"
+            DELETE FROM game_company WHERE app_id = $1 AND role = $2 AND NOT (company_id = ANY ($3))
+        "#;
+        let company_insert_q = r#"
+            INSERT INTO game_company (app_id, role, company_id) SELECT $1, $2, UNNEST($3::INTEGER[])
+            ON CONFLICT DO NOTHING
+        "#;
+        let platform_delete_q = "DELETE FROM game_platform WHERE app_id = $1 AND NOT (platform = ANY ($2))";
+        let platform_insert_q = r#"
+            INSERT INTO game_platform (app_id, platform) SELECT $1, UNNEST($2::TEXT[])
+            ON CONFLICT DO NOTHING
+        "#;
+
+        println!("Updating categories, genres, companies and platforms for {} games", details.len());
+
+        for d in details {
+            let app_id: i64 = d.id.into();
+
+            let category_ids: Vec<u32> = d.categories.iter().map(|c| c.id).collect();
+            self.replace_game_links("category", app_id, &category_ids).await?;
+
+            let genre_ids: Vec<u32> = d.genres.iter().map(|g| g.id).collect();
+            self.replace_game_links("genre", app_id, &genre_ids).await?;
+
+            for (role, names) in [("developer", &d.developers), ("publisher", &d.publishers)] {
+                let ids: Vec<i32> = names.iter().filter_map(|n| company_ids.get(n)).copied().collect();
+                self.db.execute(company_delete_q, &[&app_id, &role, &ids]).await?;
+                self.db.execute(company_insert_q, &[&app_id, &role, &ids]).await?;
+            }
+
+            let platforms: Vec<&str> = d.platforms.iter().map(|p| p.as_str()).collect();
+            self.db.execute(platform_delete_q, &[&app_id, &platforms]).await?;
+            self.db.execute(platform_insert_q, &[&app_id, &platforms]).await?;
+        }
+
+        let version_q = "UPDATE game_details SET metadata_version = $1 WHERE app_id = ANY ($2)";
+        let app_ids: Vec<i64> = details.iter().map(|d| d.id.into()).collect();
+        self.db.execute(version_q, &[&GAME_METADATA_VERSION, &app_ids]).await?;
+
+        Ok(())
+    }
+
+    async fn mark_game_detail_failures(&self, games: &[GameId], failed: &DateTime<Utc>) -> () {
         if games.len() == 0 {
             return ();
         }
@@ -251,13 +596,16 @@ impl GameDetailsHandling for Repo {
 
         // Insert 1 failure into the blacklist table or increment the value already present
         let q = r#"
-            INSERT INTO game_details_blacklist (app_id, failure_count)
-            VALUES ($1, 1)
-            ON CONFLICT (app_id) DO UPDATE SET failure_count = game_details_blacklist.failure_count + 1;
+            INSERT INTO game_details_blacklist (app_id, failure_count, last_failed)
+            VALUES ($1, 1, $2)
+            ON CONFLICT (app_id) DO UPDATE
+                SET failure_count = game_details_blacklist.failure_count + 1,
+                    last_failed = excluded.last_failed;
         "#;
 
+        let failed = failed.naive_utc();
         for id in games {
-            let res = self.db.execute(q, &[&Into::<i64>::into(id.clone())]).await;
+            let res = self.tolerate(self.db.execute(q, &[&Into::<i64>::into(id.clone()), &failed])).await;
             if let Err(err) = res {
                 eprintln!("Failed to increment failure count for id {}: {}", &id, &err);
             }
@@ -276,6 +624,54 @@ impl GameDetailsHandling for Repo {
                 .collect()
         )
     }
+
+    /// Every distinct release date text in game_details, most common first
+    async fn get_release_date_texts(&self) -> Result<Vec<ReleaseDateText>> {
+        let q = r#"
+            SELECT
+                gd.release_date,
+                COUNT(*),
+                MIN(gd.app_id),
+                (ARRAY_AGG(sg.name ORDER BY gd.app_id))[1]
+            FROM
+                game_details gd
+                LEFT JOIN steam_game sg ON gd.app_id = sg.app_id
+            WHERE
+                gd.release_date IS NOT NULL AND gd.release_date <> ''
+            GROUP BY
+                gd.release_date
+            ORDER BY
+                COUNT(*) DESC, gd.release_date
+        "#;
+
+        Ok(
+            self.db
+                .query(q, &[]).await?
+                .into_iter()
+                .map(|row| ReleaseDateText {
+                    text: row.get(0),
+                    game_count: row.get(1),
+                    example: GameId::from(row.get::<usize, i64>(2)),
+                    example_name: row.get(3),
+                })
+                .collect()
+        )
+    }
+
+    /// Of the given games, those whose details were last recorded as not yet released
+    async fn get_unreleased_games(&self, games: &[&GameId]) -> Result<HashSet<GameId>> {
+        let q = r#"SELECT app_id FROM game_details WHERE app_id = ANY ($1) AND is_released = FALSE"#;
+        let ids: Vec<i64> = games.iter().map(|&id| Into::<i64>::into(*id)).collect();
+
+        Ok(
+            self.db
+                .query(q, &[&ids])
+                .await?
+                .into_iter()
+                .map(|row| GameId::from(row.get::<usize, i64>(0)))
+                .collect()
+        )
+    }
 }
 
 impl OwnedGamesHandling for Repo {
@@ -288,7 +684,7 @@ impl OwnedGamesHandling for Repo {
         let mut row_count: u64 = 0;
 
         for id in games.into_iter() {
-            match self.db.execute(q, &[&Into::<i64>::into(id.clone()), &now]).await {
+            match self.tolerate(self.db.execute(q, &[&Into::<i64>::into(id.clone()), &now])).await {
                 Ok(c) => row_count += c,
                 Err(e) => eprintln!("Couldn't insert game {}: {}", id.app_id, e),
             }
@@ -297,6 +693,25 @@ impl OwnedGamesHandling for Repo {
         println!("Inserted {} new owned games into owned_game table", row_count);
         Ok(())
     }
+
+    /// Retrieve owned games which aren't out yet, e.g. pre-orders, by checking game_details
+    async fn get_upcoming_owned_game_ids(&self) -> Result<Vec<GameId>> {
+        let q = r#"
+            SELECT og.app_id
+            FROM owned_game og JOIN game_details gd ON og.app_id = gd.app_id
+            WHERE
+                gd.is_released = FALSE OR
+                gd.release_estimate IS NOT NULL AND gd.release_estimate > NOW()
+        "#;
+
+        Ok(
+            self.db
+                .query(q, &[]).await?
+                .into_iter()
+                .map(|row| GameId::from(row.get::<usize, i64>(0)))
+                .collect()
+        )
+    }
 }
 
 impl PlayedGamesHandling for Repo {
@@ -431,113 +846,394 @@ impl NotedGamesHandling for Repo {
                 .collect()
         )
     }
+
+    async fn set_noted_game_app_id(&self, note_id: &str, app_id: &GameId) -> Result<()> {
+        let q = r#"UPDATE noted_game SET app_id = $2 WHERE note_id = $1"#;
+
+        self.db.execute(q, &[&note_id, &Into::<i64>::into(*app_id)]).await?;
+        Ok(())
+    }
 }
 
-impl Repo {
-    async fn get_wishlisted_ids(&self) -> Result<HashSet<GameId>> {
-        let q = r#"SELECT app_id FROM wishlist WHERE deleted IS NULL"#;
+impl AppIdCandidateHandling for Repo {
+    /// Get candidates which have been rejected for a note, so we don't suggest them again
+    async fn get_rejected_candidates(&self) -> Result<HashSet<(String, GameId)>> {
+        let q = r#"SELECT note_id, app_id FROM app_id_candidate WHERE status = 'rejected'"#;
 
         Ok(
             self.db
                 .query(q, &[]).await?
                 .into_iter()
-                .map(|row| GameId::from(row.get::<usize, i64>(0)))
+                .map(|row| (row.get(0), GameId::from(row.get::<usize, i64>(1))))
                 .collect()
         )
     }
 
-    async fn delete_wishlist_ids(&self, ids: &[&GameId]) -> Result<()> {
-        let now = Utc::now().naive_utc();
-        let q = r#"UPDATE wishlist SET deleted = $2 WHERE app_id = ANY ($1)"#;
+    /// Record candidates, updating the score of any already known without changing their status
+    async fn insert_app_id_candidates(&self, candidates: &[AppIdCandidate]) -> Result<()> {
+        let q = r#"
+            INSERT INTO app_id_candidate (note_id, note_name, app_id, score, status)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (note_id, app_id) DO UPDATE
+                SET note_name = excluded.note_name,
+                    score = excluded.score
+        "#;
 
-        self.db
-            .execute(
-                q,
-                &[
-                    &ids.iter().map(|&id| Into::<i64>::into(id.clone())).collect::<Vec<_>>(),
-                    &now,
-                ]
-            )
-            .await?;
+        println!("Recording {} app ID candidates for unmatched notes", candidates.len());
 
+        for c in candidates {
+            let status: String = c.status.clone().into();
+            self.db
+                .execute(
+                    q,
+                    &[&c.note_id, &c.note_name, &Into::<i64>::into(c.app_id), &c.score, &status]
+                )
+                .await?;
+        }
         Ok(())
     }
 
-    async fn insert_wishlist_items(&self, items: &[WishlistedGame]) -> Result<()> {
-        // If an item was deleted and readded, unmark as deleted but keep original add date
+    /// Get candidates awaiting review, best first for each note. Notes which have since been
+    /// given an app ID some other way are left out.
+    async fn get_pending_candidates(&self) -> Result<Vec<(i32, AppIdCandidate)>> {
         let q = r#"
-            INSERT INTO wishlist (app_id, wishlisted) VALUES ($1, $2)
-            ON CONFLICT (app_id) DO UPDATE SET deleted = NULL
+            SELECT c.id, c.note_id, c.note_name, c.app_id, sg.name, c.score, c.status
+            FROM
+                app_id_candidate c
+                JOIN noted_game ng ON c.note_id = ng.note_id
+                LEFT JOIN steam_game sg ON c.app_id = sg.app_id
+            WHERE
+                c.status = 'pending' AND
+                ng.app_id IS NULL
+            ORDER BY c.note_name, c.score DESC
         "#;
 
-        for item in items {
+        Ok(
             self.db
-                .execute(q, &[&Into::<i64>::into(item.id.clone()), &item.wishlisted.naive_utc()])
-                .await?;
-        }
-        Ok(())
+                .query(q, &[]).await?
+                .into_iter()
+                .map(|row| (row.get(0), Self::candidate_from_row(&row, 1)))
+                .collect()
+        )
     }
-}
 
-impl WishlistHandling for Repo {
-    /// Sync the wishlist by marking removed items as deleted and then inserting missing items
-    // TODO: it'd be best to do this transactionally, see link above
-    async fn update_wishlist(&self, items: &[WishlistedGame]) -> Result<()> {
-        let existing_ids = self.get_wishlisted_ids().await?;
-        let new_ids: HashSet<GameId> = items.iter().map(|item| item.id.clone()).collect();
-        let remove_ids: Vec<&GameId> = existing_ids.difference(&new_ids).collect();
+    async fn get_candidate(&self, id: i32) -> Result<Option<AppIdCandidate>> {
+        let q = r#"
+            SELECT c.note_id, c.note_name, c.app_id, sg.name, c.score, c.status
+            FROM app_id_candidate c LEFT JOIN steam_game sg ON c.app_id = sg.app_id
+            WHERE c.id = $1
+        "#;
 
-        println!("Marking {} wishlist items as deleted...", remove_ids.len());
-        if !remove_ids.is_empty() {
-            self.delete_wishlist_ids(&remove_ids).await?;
-        }
+        Ok(self.db.query_opt(q, &[&id]).await?.map(|row| Self::candidate_from_row(&row, 0)))
+    }
 
-        let new_items: Vec<WishlistedGame> = {
-            items.iter().cloned().filter(|item| !existing_ids.contains(&item.id)).collect()
-        };
+    /// Accept or reject a candidate. Accepting a candidate sets the app ID on the noted game and
+    /// discards any other pending candidates for the same note.
+    async fn resolve_candidate(&self, id: i32, status: &CandidateStatus) -> Result<()> {
+        let q = r#"UPDATE app_id_candidate SET status = $2 WHERE id = $1"#;
+        let status_str: String = status.clone().into();
+        self.db.execute(q, &[&id, &status_str]).await?;
 
-        println!("Inserting {} new wishlist items...", new_items.len());
-        if !new_items.is_empty() {
-            self.insert_wishlist_items(&new_items).await?;
+        if *status != CandidateStatus::Accepted {
+            return Ok(());
         }
 
+        if let Some(c) = self.get_candidate(id).await? {
+            self.set_noted_game_app_id(&c.note_id, &c.app_id).await?;
+
+            let cleanup_q = r#"
+                DELETE FROM app_id_candidate WHERE note_id = $1 AND status = 'pending'
+            "#;
+            self.db.execute(cleanup_q, &[&c.note_id]).await?;
+        }
         Ok(())
     }
+}
 
-    /// Retrieve upcoming wishlisted games by checking the game_details table for release state
-    async fn get_upcoming_wishlisted_game_ids(&self) -> Result<Vec<GameId>> {
-        let q = r#"
-            SELECT w.app_id
-            FROM wishlist w LEFT JOIN game_details gd ON w.app_id = gd.app_id
-            WHERE
-                gd.is_released = FALSE OR
-                gd.release_estimate IS NOT NULL AND gd.release_estimate > NOW()
-        "#;
+impl Repo {
+    // Read an AppIdCandidate from a row with columns starting at the given offset, in the order
+    // note_id, note_name, app_id, app name, score, status
+    fn candidate_from_row(row: &Row, offset: usize) -> AppIdCandidate {
+        AppIdCandidate {
+            note_id: row.get(offset),
+            note_name: row.get(offset + 1),
+            app_id: GameId::from(row.get::<usize, i64>(offset + 2)),
+            app_name: row.get::<usize, Option<String>>(offset + 3).unwrap_or_default(),
+            score: row.get(offset + 4),
+            status: CandidateStatus::from(row.get::<usize, String>(offset + 5)),
+        }
+    }
+}
+
+impl Repo {
+    /// Make sure all the given tag names exist in the tag table and return their IDs by name
+    async fn upsert_tags(&self, names: &HashSet<&str>) -> Result<HashMap<String, i32>> {
+        let insert_q = "INSERT INTO tag (name) VALUES ($1) ON CONFLICT (name) DO NOTHING";
+        for name in names {
+            self.db.execute(insert_q, &[name]).await?;
+        }
+
+        let select_q = "SELECT id, name FROM tag WHERE name = ANY ($1)";
+        let owned_names: Vec<&str> = names.iter().copied().collect();
 
         Ok(
             self.db
-                .query(q, &[]).await?
+                .query(select_q, &[&owned_names]).await?
                 .into_iter()
-                .map(|row| GameId::from(row.get::<usize, i64>(0)))
+                .map(|row| (row.get(1), row.get(0)))
                 .collect()
         )
     }
 }
 
-impl ReleaseUpdateHandling for Repo {
+impl Repo {
+    /// Make sure all the given entries exist in a lookup table keyed on steam's IDs, i.e. genre
+    /// or category, with their latest descriptions
+    async fn upsert_descriptions(&self, table: &str, entries: &HashMap<u32, &str>) -> Result<()> {
+        let q = format!(
+            r#"
+                INSERT INTO {} (id, description) VALUES ($1, $2)
+                ON CONFLICT (id) DO UPDATE SET description = excluded.description
+            "#,
+            table
+        );
+        for (id, description) in entries {
+            self.db.execute(&q, &[&i32::try_from(*id)?, description]).await?;
+        }
+        Ok(())
+    }
+
+    /// Set which entries of a lookup table a game is linked to, via game_{table}, e.g. its genres
+    async fn replace_game_links(&self, table: &str, app_id: i64, ids: &[u32]) -> Result<()> {
+        let ids: Vec<i32> = ids.iter().map(|id| i32::try_from(*id)).collect::<std::result::Result<_, _>>()?;
+
+        let delete_q = format!("DELETE FROM game_{0} WHERE app_id = $1 AND NOT ({0}_id = ANY ($2))", table);
+        let insert_q = format!(
+            "INSERT INTO game_{0} (app_id, {0}_id) SELECT $1, UNNEST($2::INTEGER[]) ON CONFLICT DO NOTHING",
+            table
+        );
+
+        self.db.execute(&delete_q, &[&app_id, &ids]).await?;
+        self.db.execute(&insert_q, &[&app_id, &ids]).await?;
+        Ok(())
+    }
+
+    /// Make sure all the given developers and publishers exist in the company table and return
+    /// their IDs by name
+    async fn upsert_companies(&self, names: &HashSet<&str>) -> Result<HashMap<String, i32>> {
+        let insert_q = "INSERT INTO company (name) VALUES ($1) ON CONFLICT (name) DO NOTHING";
+        for name in names {
+            self.db.execute(insert_q, &[name]).await?;
+        }
+
+        let select_q = "SELECT id, name FROM company WHERE name = ANY ($1)";
+        let owned_names: Vec<&str> = names.iter().copied().collect();
+
+        Ok(
+            self.db
+                .query(select_q, &[&owned_names]).await?
+                .into_iter()
+                .map(|row| (row.get(1), row.get(0)))
+                .collect()
+        )
+    }
+}
+
+impl GameTagHandling for Repo {
+    /// Sync game tags with those currently set on each note, removing any tags no longer present
+    /// on the note, and any tags no longer used by any note at all
+    async fn update_game_tags(&self, notes: &[NotedGame]) -> Result<()> {
+        let names: HashSet<&str> = notes.iter().flat_map(|n| n.tags.iter().map(|t| t.as_str())).collect();
+        let tag_ids = self.upsert_tags(&names).await?;
+
+        let insert_q = r#"
+            INSERT INTO game_tag (note_id, app_id, tag_id) VALUES ($1, $2, $3)
+            ON CONFLICT (note_id, tag_id) DO UPDATE SET app_id = excluded.app_id
+        "#;
+        let delete_q = "DELETE FROM game_tag WHERE note_id = $1 AND NOT (tag_id = ANY ($2))";
+
+        println!("Updating tags for {} game notes across {} tags", notes.len(), tag_ids.len());
+
+        for n in notes {
+            let app_id: Option<i64> = n.app_id.map(|id| id.into());
+            let ids: Vec<i32> = n.tags.iter().filter_map(|t| tag_ids.get(t)).copied().collect();
+
+            for tag_id in &ids {
+                self.db.execute(insert_q, &[&n.note_id, &app_id, tag_id]).await?;
+            }
+            self.db.execute(delete_q, &[&n.note_id, &ids]).await?;
+        }
+
+        let cleanup_q = "DELETE FROM tag WHERE id NOT IN (SELECT tag_id FROM game_tag WHERE tag_id IS NOT NULL)";
+        let removed = self.db.execute(cleanup_q, &[]).await?;
+        println!("Removed {} tags no longer used by any game", removed);
+
+        Ok(())
+    }
+}
+
+impl Repo {
+    async fn delete_wishlist_ids(&self, ids: &[&GameId]) -> Result<()> {
+        let now = Utc::now().naive_utc();
+        let q = r#"UPDATE wishlist SET deleted = $2 WHERE app_id = ANY ($1)"#;
+
+        self.db
+            .execute(
+                q,
+                &[
+                    &ids.iter().map(|&id| Into::<i64>::into(id.clone())).collect::<Vec<_>>(),
+                    &now,
+                ]
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn insert_wishlist_items(&self, items: &[WishlistedGame]) -> Result<()> {
+        // If an item was deleted and readded, unmark as deleted but keep original add date
+        let q = r#"
+            INSERT INTO wishlist (app_id, wishlisted) VALUES ($1, $2)
+            ON CONFLICT (app_id) DO UPDATE SET deleted = NULL
+        "#;
+
+        for item in items {
+            self.db
+                .execute(q, &[&Into::<i64>::into(item.id.clone()), &item.wishlisted.naive_utc()])
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+impl WishlistHandling for Repo {
+    async fn get_wishlisted_ids(&self) -> Result<HashSet<GameId>> {
+        let q = r#"SELECT app_id FROM wishlist WHERE deleted IS NULL"#;
+
+        Ok(
+            self.db
+                .query(q, &[]).await?
+                .into_iter()
+                .map(|row| GameId::from(row.get::<usize, i64>(0)))
+                .collect()
+        )
+    }
+
+    /// Sync the wishlist by marking removed items as deleted and then inserting missing items
+    // TODO: it'd be best to do this transactionally, see link above
+    async fn update_wishlist(&self, items: &[WishlistedGame]) -> Result<()> {
+        let existing_ids = self.get_wishlisted_ids().await?;
+        let new_ids: HashSet<GameId> = items.iter().map(|item| item.id.clone()).collect();
+        let remove_ids: Vec<&GameId> = existing_ids.difference(&new_ids).collect();
+
+        println!("Marking {} wishlist items as deleted...", remove_ids.len());
+        if !remove_ids.is_empty() {
+            self.delete_wishlist_ids(&remove_ids).await?;
+        }
+
+        let new_items: Vec<WishlistedGame> = {
+            items.iter().cloned().filter(|item| !existing_ids.contains(&item.id)).collect()
+        };
+
+        println!("Inserting {} new wishlist items...", new_items.len());
+        if !new_items.is_empty() {
+            self.insert_wishlist_items(&new_items).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Retrieve upcoming wishlisted games by checking the game_details table for release state
+    async fn get_upcoming_wishlisted_game_ids(&self) -> Result<Vec<GameId>> {
+        let q = r#"
+            SELECT w.app_id
+            FROM wishlist w LEFT JOIN game_details gd ON w.app_id = gd.app_id
+            WHERE
+                gd.is_released = FALSE OR
+                gd.release_estimate IS NOT NULL AND gd.release_estimate > NOW()
+        "#;
+
+        Ok(
+            self.db
+                .query(q, &[]).await?
+                .into_iter()
+                .map(|row| GameId::from(row.get::<usize, i64>(0)))
+                .collect()
+        )
+    }
+
+    /// Unreleased games on the wishlist, with their developers, publishers and how many times
+    /// their release has been delayed
+    async fn get_upcoming_wishlist(&self) -> Result<Vec<UpcomingGame>> {
+        // N.B. the delayed changes mirror ReleaseChange::is_delay
+        let q = r#"
+            SELECT
+                w.app_id,
+                sg.name,
+                gd.release_date,
+                gd.release_estimate,
+                ARRAY(
+                    SELECT c.name
+                    FROM game_company gc JOIN company c ON gc.company_id = c.id
+                    WHERE gc.app_id = w.app_id AND gc.role = 'developer'
+                    ORDER BY c.name
+                ),
+                ARRAY(
+                    SELECT c.name
+                    FROM game_company gc JOIN company c ON gc.company_id = c.id
+                    WHERE gc.app_id = w.app_id AND gc.role = 'publisher'
+                    ORDER BY c.name
+                ),
+                (
+                    SELECT COUNT(*)
+                    FROM release_update ru
+                    WHERE ru.app_id = w.app_id AND ru.change IN ('postponed', 'widened', 'tba')
+                )
+            FROM
+                wishlist w
+                JOIN game_details gd ON w.app_id = gd.app_id
+                LEFT JOIN steam_game sg ON w.app_id = sg.app_id
+            WHERE
+                w.deleted IS NULL AND
+                gd.is_released = FALSE
+            ORDER BY
+                gd.release_estimate NULLS LAST,
+                sg.name
+        "#;
+
+        Ok(
+            self.db
+                .query(q, &[]).await?
+                .into_iter()
+                .map(|row| UpcomingGame {
+                    id: GameId::from(row.get::<usize, i64>(0)),
+                    name: row.get(1),
+                    release_date: row.get(2),
+                    release_estimate: row.get::<usize, Option<NaiveDateTime>>(3).map(|t| t.and_utc()),
+                    developers: row.get(4),
+                    publishers: row.get(5),
+                    delays: row.get(6),
+                })
+                .collect()
+        )
+    }
+}
+
+impl ReleaseUpdateHandling for Repo {
     async fn insert_release_update(
         &self,
         game: &GameId,
         prev_text: &str,
         prev_date: &Option<DateTime<Utc>>,
         new_text: &str,
-        new_date: &Option<DateTime<Utc>>
+        new_date: &Option<DateTime<Utc>>,
+        change: ReleaseChange
     ) -> Result<()> {
         let q = r#"
             INSERT INTO release_update (
-                app_id, prev_text, new_text, prev_estimate, new_estimate, recorded
+                app_id, prev_text, new_text, prev_estimate, new_estimate, change, recorded
             )
-            VALUES ($1, $2, $3, $4, $5, $6);
+            VALUES ($1, $2, $3, $4, $5, $6, $7);
         "#;
         let now = Utc::now().naive_utc();
 
@@ -550,11 +1246,532 @@ impl ReleaseUpdateHandling for Repo {
                     &new_text.to_string(),
                     &prev_date.map(|d| d.naive_utc()),
                     &new_date.map(|d| d.naive_utc()),
+                    &change.as_str(),
                     &now,
                 ]
             ).await?;
         Ok(())
     }
+
+    async fn get_release_updates_since(&self, since: &DateTime<Utc>) -> Result<Vec<ReleaseUpdate>> {
+        let q = r#"
+            SELECT
+                ru.id,
+                ru.app_id,
+                sg.name,
+                ru.prev_text,
+                ru.new_text,
+                ru.prev_estimate,
+                ru.new_estimate,
+                ru.recorded,
+                ru.change
+            FROM
+                release_update ru
+                LEFT JOIN steam_game sg ON ru.app_id = sg.app_id
+            WHERE
+                ru.recorded >= $1
+            ORDER BY
+                ru.recorded DESC,
+                ru.id DESC
+        "#;
+
+        Ok(
+            self.db
+                .query(q, &[&since.naive_utc()]).await?
+                .into_iter()
+                .map(|row| ReleaseUpdate {
+                    id: row.get(0),
+                    game: GameId::from(row.get::<usize, i64>(1)),
+                    name: row.get(2),
+                    prev_text: row.get(3),
+                    new_text: row.get(4),
+                    prev_estimate: row.get::<usize, Option<NaiveDateTime>>(5).map(|t| t.and_utc()),
+                    new_estimate: row.get::<usize, Option<NaiveDateTime>>(6).map(|t| t.and_utc()),
+                    change: {
+                        row.get::<usize, Option<&str>>(8)
+                            .and_then(|c| ReleaseChange::try_from(c).ok())
+                            .unwrap_or_default()
+                    },
+                    recorded: row.get::<usize, NaiveDateTime>(7).and_utc(),
+                })
+                .collect()
+        )
+    }
+
+    /// Get the id, previous and new text of release updates recorded before changes were classified
+    async fn get_unclassified_release_updates(&self) -> Result<Vec<(i32, String, String)>> {
+        let q = r#"
+            SELECT id, COALESCE(prev_text, ''), COALESCE(new_text, '')
+            FROM release_update
+            WHERE change IS NULL
+        "#;
+
+        Ok(
+            self.db
+                .query(q, &[]).await?
+                .into_iter()
+                .map(|row| (row.get(0), row.get(1), row.get(2)))
+                .collect()
+        )
+    }
+
+    async fn set_release_update_changes(&self, changes: &[(i32, ReleaseChange)]) -> Result<u64> {
+        let q = r#"
+            UPDATE release_update ru SET change = c.change
+            FROM UNNEST($1::INTEGER[], $2::TEXT[]) AS c (id, change)
+            WHERE ru.id = c.id
+        "#;
+
+        let ids: Vec<i32> = changes.iter().map(|(id, _)| *id).collect();
+        let kinds: Vec<&str> = changes.iter().map(|(_, c)| c.as_str()).collect();
+
+        Ok(self.db.execute(q, &[&ids, &kinds]).await?)
+    }
+
+    /// Every release date change, once for each developer and publisher of the game, along with
+    /// their games whose release date has never changed
+    async fn get_studio_release_updates(&self) -> Result<Vec<StudioReleaseUpdate>> {
+        let q = r#"
+            SELECT
+                c.name,
+                gc.role,
+                gc.app_id,
+                ru.change,
+                ru.prev_estimate,
+                ru.new_estimate,
+                ru.id IS NOT NULL
+            FROM
+                game_company gc
+                JOIN company c ON gc.company_id = c.id
+                JOIN game_details gd ON gc.app_id = gd.app_id
+                LEFT JOIN release_update ru ON gc.app_id = ru.app_id
+            ORDER BY
+                c.name,
+                gc.role,
+                gc.app_id,
+                ru.recorded
+        "#;
+
+        Ok(
+            self.db
+                .query(q, &[]).await?
+                .into_iter()
+                .map(|row| StudioReleaseUpdate {
+                    studio: row.get(0),
+                    role: row.get(1),
+                    game: GameId::from(row.get::<usize, i64>(2)),
+                    change: row.get::<usize, bool>(6).then(|| {
+                        row.get::<usize, Option<&str>>(3)
+                            .and_then(|c| ReleaseChange::try_from(c).ok())
+                            .unwrap_or_default()
+                    }),
+                    prev_estimate: row.get::<usize, Option<NaiveDateTime>>(4).map(|t| t.and_utc()),
+                    new_estimate: row.get::<usize, Option<NaiveDateTime>>(5).map(|t| t.and_utc()),
+                })
+                .collect()
+        )
+    }
+}
+
+impl GamePriceHandling for Repo {
+    /// Get games whose prices we want to track: anything currently wishlisted or noted in notion
+    async fn get_price_tracked_game_ids(&self) -> Result<Vec<GameId>> {
+        let q = r#"
+            SELECT app_id FROM wishlist WHERE deleted IS NULL
+            UNION
+            SELECT app_id FROM noted_game WHERE app_id IS NOT NULL
+        "#;
+
+        Ok(
+            self.db
+                .query(q, &[]).await?
+                .into_iter()
+                .map(|row| GameId::from(row.get::<usize, i64>(0)))
+                .collect()
+        )
+    }
+
+    /// Summarise the latest and lowest prices recorded so far for each of the given games
+    async fn get_price_history(&self, games: &[GameId]) -> Result<HashMap<GameId, PriceHistory>> {
+        let q = r#"
+            SELECT DISTINCT ON (app_id)
+                app_id,
+                price,
+                discount_percent,
+                MIN(price) OVER (PARTITION BY app_id)
+            FROM game_price
+            WHERE app_id = ANY ($1)
+            ORDER BY app_id, recorded DESC
+        "#;
+
+        let ids: Vec<i64> = games.iter().map(|&id| id.into()).collect();
+        let mut history = HashMap::new();
+
+        for row in self.db.query(q, &[&ids]).await? {
+            let summary = PriceHistory {
+                latest_price: u32::try_from(row.get::<usize, i32>(1))?,
+                latest_discount_percent: u8::try_from(row.get::<usize, i32>(2))?,
+                lowest_price: u32::try_from(row.get::<usize, i32>(3))?,
+            };
+            history.insert(GameId::from(row.get::<usize, i64>(0)), summary);
+        }
+
+        Ok(history)
+    }
+
+    async fn insert_game_prices(&self, prices: &[GamePrice]) -> Result<()> {
+        let q = r#"
+            INSERT INTO game_price (app_id, price, discount_percent, recorded)
+            VALUES ($1, $2, $3, $4)
+        "#;
+
+        let mut row_count: u64 = 0;
+
+        println!("Inserting {} prices into game_price table", prices.len());
+        for p in prices {
+            let res = self.tolerate(
+                self.db.execute(
+                    q,
+                    &[
+                        &Into::<i64>::into(p.id),
+                        &i32::try_from(p.price)?,
+                        &i32::from(p.discount_percent),
+                        &p.recorded.naive_utc(),
+                    ]
+                )
+            ).await;
+
+            match res {
+                Ok(c) => row_count += c,
+                Err(e) => eprintln!("Couldn't insert price for game {}: {}", p.id.app_id, e),
+            }
+        }
+
+        println!("Inserted {} new prices into game_price table", row_count);
+        Ok(())
+    }
+}
+
+impl TargetPriceHandling for Repo {
+    async fn get_target_prices(&self) -> Result<HashMap<GameId, u32>> {
+        let q = r#"SELECT app_id, price FROM target_price"#;
+
+        let mut targets = HashMap::new();
+        for row in self.db.query(q, &[]).await? {
+            targets.insert(GameId::from(row.get::<usize, i64>(0)), u32::try_from(row.get::<usize, i32>(1))?);
+        }
+        Ok(targets)
+    }
+
+    async fn set_target_price(&self, game: &GameId, price: u32) -> Result<()> {
+        let q = r#"
+            INSERT INTO target_price (app_id, price, set_at) VALUES ($1, $2, NOW())
+            ON CONFLICT (app_id) DO UPDATE
+                SET price = excluded.price,
+                    set_at = excluded.set_at
+        "#;
+
+        self.db.execute(q, &[&Into::<i64>::into(*game), &i32::try_from(price)?]).await?;
+        Ok(())
+    }
+
+    async fn delete_target_price(&self, game: &GameId) -> Result<()> {
+        let q = r#"DELETE FROM target_price WHERE app_id = $1"#;
+
+        self.db.execute(q, &[&Into::<i64>::into(*game)]).await?;
+        Ok(())
+    }
+}
+
+impl GameDetailsBlacklistHandling for Repo {
+    async fn get_blacklist(&self) -> Result<Vec<BlacklistedGame>> {
+        let q = r#"
+            SELECT
+                blacklist.app_id,
+                steam_game.name,
+                blacklist.failure_count,
+                blacklist.last_failed
+            FROM
+                game_details_blacklist blacklist
+                LEFT JOIN steam_game ON blacklist.app_id = steam_game.app_id
+            ORDER BY
+                blacklist.failure_count DESC,
+                blacklist.last_failed DESC
+        "#;
+
+        let mut entries = vec![];
+        for row in self.db.query(q, &[]).await? {
+            entries.push(
+                BlacklistedGame {
+                    id: GameId::from(row.get::<usize, i64>(0)),
+                    name: row.get(1),
+                    failure_count: u32::try_from(row.get::<usize, i32>(2))?,
+                    last_failed: row.get::<usize, Option<NaiveDateTime>>(3).map(|t| t.and_utc()),
+                }
+            );
+        }
+        Ok(entries)
+    }
+
+    async fn delete_blacklist_entries(&self, games: &[GameId]) -> Result<u64> {
+        let q = r#"DELETE FROM game_details_blacklist WHERE app_id = ANY ($1)"#;
+
+        let ids: Vec<i64> = games.iter().map(|&id| id.into()).collect();
+        Ok(self.db.execute(q, &[&ids]).await?)
+    }
+
+    async fn clear_blacklist(&self) -> Result<u64> {
+        let q = r#"DELETE FROM game_details_blacklist"#;
+
+        Ok(self.db.execute(q, &[]).await?)
+    }
+}
+
+impl SyncRunHandling for Repo {
+    async fn start_sync_run(&self, started: &DateTime<Utc>) -> Result<i32> {
+        let q = r#"INSERT INTO sync_run (started, status) VALUES ($1, 'running') RETURNING id"#;
+
+        let row = self.tolerate(self.db.query_one(q, &[&started.naive_utc()])).await?;
+        Ok(row.get(0))
+    }
+
+    async fn finish_sync_run(
+        &self,
+        id: i32,
+        finished: &DateTime<Utc>,
+        status: &SyncStatus,
+        error: Option<&str>
+    ) -> Result<()> {
+        let q = r#"UPDATE sync_run SET finished = $2, status = $3, error = $4 WHERE id = $1"#;
+
+        let status: String = status.clone().into();
+        self.tolerate(self.db.execute(q, &[&id, &finished.naive_utc(), &status, &error])).await?;
+        Ok(())
+    }
+
+    async fn insert_sync_phase(&self, run_id: i32, phase: &SyncPhaseRun) -> Result<()> {
+        let q = r#"
+            INSERT INTO sync_run_phase (run_id, phase, started, finished, status, item_count, error)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#;
+
+        let status: String = phase.status.clone().into();
+        let item_count = phase.item_count.map(i32::try_from).transpose()?;
+        self.tolerate(
+            self.db.execute(
+                q,
+                &[
+                    &run_id,
+                    &phase.phase.as_str(),
+                    &phase.started.naive_utc(),
+                    &phase.finished.naive_utc(),
+                    &status,
+                    &item_count,
+                    &phase.error,
+                ]
+            )
+        ).await?;
+        Ok(())
+    }
+
+    async fn get_recent_sync_runs(&self, limit: i64) -> Result<Vec<SyncRun>> {
+        let runs_q = r#"
+            SELECT id, started, finished, status, error
+            FROM sync_run
+            ORDER BY started DESC
+            LIMIT $1
+        "#;
+        let phases_q = r#"
+            SELECT run_id, phase, started, finished, status, item_count, error
+            FROM sync_run_phase
+            WHERE run_id = ANY ($1)
+            ORDER BY started
+        "#;
+
+        let mut runs: Vec<SyncRun> = vec![];
+        for row in self.db.query(runs_q, &[&limit]).await? {
+            runs.push(
+                SyncRun {
+                    id: row.get(0),
+                    started: row.get::<usize, NaiveDateTime>(1).and_utc(),
+                    finished: row.get::<usize, Option<NaiveDateTime>>(2).map(|t| t.and_utc()),
+                    status: SyncStatus::from(row.get::<usize, String>(3)),
+                    error: row.get(4),
+                    phases: vec![],
+                }
+            );
+        }
+
+        let ids: Vec<i32> = runs.iter().map(|r| r.id).collect();
+        for row in self.db.query(phases_q, &[&ids]).await? {
+            // Skip any phases written by a newer version which we don't know about
+            let Ok(phase) = SyncPhase::try_from(row.get::<usize, &str>(1)) else { continue };
+            let run_id: i32 = row.get(0);
+
+            if let Some(run) = runs.iter_mut().find(|r| r.id == run_id) {
+                run.phases.push(
+                    SyncPhaseRun {
+                        phase,
+                        started: row.get::<usize, NaiveDateTime>(2).and_utc(),
+                        finished: row.get::<usize, NaiveDateTime>(3).and_utc(),
+                        status: SyncStatus::from(row.get::<usize, String>(4)),
+                        item_count: row.get::<usize, Option<i32>>(5).map(u64::try_from).transpose()?,
+                        error: row.get(6),
+                    }
+                );
+            }
+        }
+
+        Ok(runs)
+    }
+
+    async fn get_last_successful_phases(&self) -> Result<HashMap<SyncPhase, DateTime<Utc>>> {
+        let q = r#"
+            SELECT phase, MAX(finished)
+            FROM sync_run_phase
+            WHERE status = 'succeeded'
+            GROUP BY phase
+        "#;
+
+        let mut last_synced = HashMap::new();
+        for row in self.db.query(q, &[]).await? {
+            if let Ok(phase) = SyncPhase::try_from(row.get::<usize, &str>(0)) {
+                last_synced.insert(phase, row.get::<usize, NaiveDateTime>(1).and_utc());
+            }
+        }
+        Ok(last_synced)
+    }
+}
+
+impl Repo {
+    fn recorded_event_from_row(row: &Row) -> Option<RecordedEvent> {
+        let id: i64 = row.get(0);
+
+        // Events written by a newer version might not be understood, so just skip them
+        match row.try_get::<usize, Json<SyncEvent>>(2) {
+            Ok(Json(event)) => Some(
+                RecordedEvent {
+                    id,
+                    run_id: row.get(1),
+                    event,
+                    recorded: row.get::<usize, NaiveDateTime>(3).and_utc(),
+                }
+            ),
+            Err(e) => {
+                eprintln!("Couldn't read sync event {}: {}", id, e);
+                None
+            },
+        }
+    }
+}
+
+impl SyncEventHandling for Repo {
+    async fn insert_sync_events(&self, run_id: Option<i32>, events: &[SyncEvent]) -> Result<()> {
+        let q = r#"
+            INSERT INTO sync_event (run_id, app_id, event_type, payload, recorded)
+            VALUES ($1, $2, $3, $4, $5)
+        "#;
+
+        let now = Utc::now().naive_utc();
+        for e in events {
+            self.tolerate(
+                self.db.execute(q, &[&run_id, &Into::<i64>::into(*e.game()), &e.kind(), &Json(e), &now])
+            ).await?;
+        }
+        Ok(())
+    }
+
+    async fn get_sync_events_since(&self, since: &DateTime<Utc>) -> Result<Vec<RecordedEvent>> {
+        let q = r#"
+            SELECT id, run_id, payload, recorded
+            FROM sync_event
+            WHERE recorded >= $1
+            ORDER BY recorded, id
+        "#;
+
+        Ok(
+            self.db
+                .query(q, &[&since.naive_utc()]).await?
+                .iter()
+                .filter_map(Self::recorded_event_from_row)
+                .collect()
+        )
+    }
+
+    async fn get_undelivered_events(
+        &self,
+        notifier: &str,
+        since: &DateTime<Utc>
+    ) -> Result<Vec<RecordedEvent>> {
+        let q = r#"
+            SELECT event.id, event.run_id, event.payload, event.recorded
+            FROM
+                sync_event event
+                LEFT JOIN sync_event_delivery delivery
+                    ON event.id = delivery.event_id AND delivery.notifier = $1
+            WHERE
+                delivery.event_id IS NULL AND
+                event.recorded >= $2
+            ORDER BY event.recorded, event.id
+        "#;
+
+        Ok(
+            self.db
+                .query(q, &[&notifier, &since.naive_utc()]).await?
+                .iter()
+                .filter_map(Self::recorded_event_from_row)
+                .collect()
+        )
+    }
+
+    async fn mark_events_delivered(&self, notifier: &str, event_ids: &[i64]) -> Result<()> {
+        let q = r#"
+            INSERT INTO sync_event_delivery (event_id, notifier, delivered)
+            SELECT id, $2, $3 FROM UNNEST($1::BIGINT[]) AS id
+            ON CONFLICT DO NOTHING
+        "#;
+
+        let now = Utc::now().naive_utc();
+        self.db.execute(q, &[&event_ids, &notifier, &now]).await?;
+        Ok(())
+    }
+
+    /// Record events the notifier isn't interested in, so they're not considered again, without
+    /// counting them as delivered
+    async fn mark_events_skipped(&self, notifier: &str, event_ids: &[i64]) -> Result<()> {
+        let q = r#"
+            INSERT INTO sync_event_delivery (event_id, notifier, delivered, skipped)
+            SELECT id, $2, $3, TRUE FROM UNNEST($1::BIGINT[]) AS id
+            ON CONFLICT DO NOTHING
+        "#;
+
+        let now = Utc::now().naive_utc();
+        self.db.execute(q, &[&event_ids, &notifier, &now]).await?;
+        Ok(())
+    }
+
+    /// When the notifier last actually sent something
+    async fn get_last_delivery(&self, notifier: &str) -> Result<Option<DateTime<Utc>>> {
+        let q = r#"SELECT MAX(delivered) FROM sync_event_delivery WHERE notifier = $1 AND NOT skipped"#;
+
+        let row = self.db.query_one(q, &[&notifier]).await?;
+        Ok(row.get::<usize, Option<NaiveDateTime>>(0).map(|t| t.and_utc()))
+    }
+
+    /// Of the given games, those which have ever had an event of the given kind recorded
+    async fn get_games_with_event(&self, kind: &str, games: &[GameId]) -> Result<HashSet<GameId>> {
+        let q = r#"SELECT DISTINCT app_id FROM sync_event WHERE event_type = $1 AND app_id = ANY ($2)"#;
+        let ids: Vec<i64> = games.iter().map(|&id| Into::<i64>::into(id)).collect();
+
+        Ok(
+            self.db
+                .query(q, &[&kind, &ids])
+                .await?
+                .into_iter()
+                .map(|row| GameId::from(row.get::<usize, i64>(0)))
+                .collect()
+        )
+    }
 }
 
 impl IgnoredGamesHandling for Repo {
@@ -1,11 +1,21 @@
-use std::collections::HashMap;
+#[cfg(test)]
+mod tests;
+
+use std::collections::{HashMap, HashSet};
 
 use chrono::{DateTime, Utc};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::db::repo::*;
-use crate::models::game::{GameId, GameDetails, GameState, NotedGame, PlayedGame};
+use crate::matching::{self, NameIndex, MAX_CANDIDATES};
+use crate::models::game::{
+    format_price, AppIdCandidate, AppType, CandidateStatus, GameId, GameDetails, GamePrice, GameState, NotedGame,
+    PlayedGame, PriceHistory, ReleaseChange
+};
 use crate::models::notion::GameNote;
+use crate::models::sync::{SyncPhase, SyncPhaseRun, SyncStatus};
 use crate::notion::{NotionError, NotionHandling};
 use crate::steam::*;
 
@@ -19,26 +29,124 @@ pub enum SyncError {
     Steam(#[from] SteamError),
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum SyncEvent {
     ReleaseDateUpdated {
         game: GameId,
         prev_text: String,
         prev_date: Option<DateTime<Utc>>,
         new_text: String,
-        new_date: Option<DateTime<Utc>>
+        new_date: Option<DateTime<Utc>>,
+        // Missing from events recorded before changes were classified
+        #[serde(default)]
+        change: ReleaseChange,
     },
     Released { game: GameId },
+    OnSale { game: GameId, price: u32, discount_percent: u8 },
+    HistoricalLow { game: GameId, price: u32, prev_low: u32 },
+    BelowTargetPrice { game: GameId, price: u32, target: u32 },
+}
+
+impl SyncEvent {
+    /// The game this event concerns
+    pub fn game(&self) -> &GameId {
+        match self {
+            SyncEvent::ReleaseDateUpdated { game, .. } => game,
+            SyncEvent::Released { game } => game,
+            SyncEvent::OnSale { game, .. } => game,
+            SyncEvent::HistoricalLow { game, .. } => game,
+            SyncEvent::BelowTargetPrice { game, .. } => game,
+        }
+    }
+
+    /// Short name for the kind of event, as stored in the sync_event table
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SyncEvent::ReleaseDateUpdated { .. } => "release_date_updated",
+            SyncEvent::Released { .. } => "released",
+            SyncEvent::OnSale { .. } => "on_sale",
+            SyncEvent::HistoricalLow { .. } => "historical_low",
+            SyncEvent::BelowTargetPrice { .. } => "below_target_price",
+        }
+    }
+
+    /// Describe the event in a single line, given the game's resolved name
+    pub fn describe(&self, name: &str) -> String {
+        match self {
+            SyncEvent::ReleaseDateUpdated { prev_text, new_text, change, .. } => {
+                let what = match change {
+                    ReleaseChange::Postponed => format!("🐌 {} has been delayed", name),
+                    ReleaseChange::BroughtForward => format!("⏩ {} has been brought forward", name),
+                    ReleaseChange::Narrowed => format!("📌 Release date narrowed down for {}", name),
+                    ReleaseChange::Widened => format!("🌫️ Release date is less certain for {}", name),
+                    ReleaseChange::Tba => format!("❓ {} no longer has a release date", name),
+                    ReleaseChange::Changed => format!("🔎 Release date changed for {}", name),
+                };
+                format!("{}: \"{}\" -> \"{}\"", what, prev_text, new_text)
+            },
+            SyncEvent::Released { .. } => {
+                format!("🚀 {} is newly released!", name)
+            },
+            SyncEvent::OnSale { price, discount_percent, .. } => {
+                format!("💸 {} is on sale: {}% off, now {}", name, discount_percent, format_price(*price))
+            },
+            SyncEvent::HistoricalLow { price, prev_low, .. } => {
+                format!(
+                    "📉 {} is at its lowest recorded price: {} (previously {})",
+                    name, format_price(*price), format_price(*prev_low)
+                )
+            },
+            SyncEvent::BelowTargetPrice { price, target, .. } => {
+                format!(
+                    "🎯 {} is below your target price: {} (target {})",
+                    name, format_price(*price), format_price(*target)
+                )
+            },
+        }
+    }
+}
+
+/// Represents a record in the sync_event table: an event as recorded by a past sync
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordedEvent {
+    pub id: i64,
+    pub run_id: Option<i32>,
+    pub event: SyncEvent,
+    pub recorded: DateTime<Utc>,
 }
 
 pub type Result<T> = std::result::Result<T, SyncError>;
 
+/// What a sync phase got done: how many items it processed, and anything worth reporting
+struct PhaseOutput {
+    item_count: usize,
+    events: Vec<SyncEvent>,
+}
+
+impl PhaseOutput {
+    fn new(item_count: usize) -> PhaseOutput {
+        PhaseOutput { item_count, events: vec![] }
+    }
+
+    fn with_events(item_count: usize, events: Vec<SyncEvent>) -> PhaseOutput {
+        PhaseOutput { item_count, events }
+    }
+}
+
 // TODO: Split into SteamSync + NotionSync and abstract over the top for better organisation
 pub struct Sync {
     steam_account_id: String,
     // FIXME: Should avoid exposing this, but this may mean Sync shouldn't own it.
     pub repo: Repo,
     steam: Box<dyn SteamHandling>,
-    notion: Box<dyn NotionHandling>
+    notion: Box<dyn NotionHandling>,
+    /// Games whose details couldn't be retrieved during the last sync, and why
+    pub skipped_details: DetailsFailures,
+    /// Games whose prices couldn't be retrieved during the last sync, and why
+    pub skipped_prices: DetailsFailures,
+    /// ID of the sync_run record for the sync in progress, if there is one
+    run_id: Option<i32>,
 }
 
 impl Sync {
@@ -48,29 +156,83 @@ impl Sync {
         steam: Box<dyn SteamHandling>,
         notion: Box<dyn NotionHandling>,
     ) -> Sync {
-        Sync { steam_account_id: steam_account_id.to_string(), repo, steam, notion }
+        Sync {
+            steam_account_id: steam_account_id.to_string(),
+            repo,
+            steam,
+            notion,
+            skipped_details: vec![],
+            skipped_prices: vec![],
+            run_id: None,
+        }
     }
 }
 
 impl Sync {
-    async fn sync_steam_games(&self) -> Result<()> {
-        let all_games: HashMap<u32, String> = self.steam
-            .get_all_games()?
-            .into_iter()
-            .map(|game| (game.appid, game.name))
-            .collect();
+    /// Record the outcome of a sync phase against the current sync run, passing on its events
+    ///
+    /// Events are kept as soon as their phase succeeds, since the state they were derived from
+    /// has already been saved and they won't be seen again, even if a later phase fails.
+    async fn record_phase(
+        &self,
+        phase: SyncPhase,
+        started: DateTime<Utc>,
+        res: Result<PhaseOutput>
+    ) -> Result<Vec<SyncEvent>> {
+        if let Ok(output) = &res {
+            if !output.events.is_empty() {
+                if let Err(e) = self.repo.insert_sync_events(self.run_id, &output.events).await {
+                    eprintln!("Failed to record {} sync events: {}", &phase, e);
+                }
+            }
+        }
+
+        let Some(run_id) = self.run_id else { return res.map(|o| o.events) };
+
+        let record = SyncPhaseRun {
+            phase,
+            started,
+            finished: Utc::now(),
+            status: if res.is_ok() { SyncStatus::Succeeded } else { SyncStatus::Failed },
+            item_count: res.as_ref().ok().map(|o| o.item_count as u64),
+            error: res.as_ref().err().map(|e| e.to_string()),
+        };
+
+        // Failing to record history shouldn't stop the sync itself
+        if let Err(e) = self.repo.insert_sync_phase(run_id, &record).await {
+            eprintln!("Failed to record {} phase of sync run {}: {}", &phase, run_id, e);
+        }
+
+        res.map(|o| o.events)
+    }
+
+    /// Bring steam_game up to date with apps added or changed in the steam catalogue since the
+    /// last sync, tracking how far we've got separately for each type of app
+    async fn sync_steam_games(&self) -> Result<PhaseOutput> {
+        let marks = self.repo.get_app_list_marks().await?;
+        let mut count = 0;
+
+        for app_type in AppType::ALL {
+            let apps = self.steam.get_app_list(app_type, marks.get(&app_type).cloned())?;
+            count += apps.len();
+
+            if let Some(latest) = apps.iter().map(|a| a.last_modified).max() {
+                self.repo.upsert_steam_apps(&apps).await?;
+                self.repo.set_app_list_mark(app_type, &latest).await?;
+            }
+        }
 
-        Ok(self.repo.insert_steam_games(all_games).await?)
+        Ok(PhaseOutput::new(count))
     }
 
-    async fn sync_owned_games(&self) -> Result<()> {
+    async fn sync_owned_games(&self) -> Result<PhaseOutput> {
         let owned_games = self.steam.get_owned_games(&self.steam_account_id)?;
         self.repo.insert_owned_games(&owned_games).await?;
 
-        Ok(())
+        Ok(PhaseOutput::new(owned_games.len()))
     }
 
-    async fn sync_played_games(&self) -> Result<()> {
+    async fn sync_played_games(&self) -> Result<PhaseOutput> {
         // FIXME: We should cache the result of get_owned_games from when we synced those and
         // reuse that rather than making an API call here (might make most sense to do it on the
         // client)
@@ -92,6 +254,30 @@ impl Sync {
 
         self.repo.insert_played_game_updates(&played_games).await?;
 
+        Ok(PhaseOutput::new(played_games.len()))
+    }
+
+    /// Classify release updates recorded before the kind of each change was, from their text, in
+    /// the same way as new ones
+    async fn classify_release_updates(&self) -> Result<()> {
+        let unclassified = self.repo.get_unclassified_release_updates().await?;
+        if unclassified.is_empty() {
+            return Ok(());
+        }
+
+        let changes: Vec<(i32, ReleaseChange)> = {
+            unclassified
+                .iter()
+                .map(|(id, prev, new)| {
+                    let prev_window = release_date::parse_release_window(prev);
+                    let new_window = release_date::parse_release_window(new);
+                    (*id, ReleaseChange::classify(prev_window.as_ref(), new_window.as_ref()))
+                })
+                .collect()
+        };
+
+        let count = self.repo.set_release_update_changes(&changes).await?;
+        println!("Classified {} earlier release date changes", count);
         Ok(())
     }
 
@@ -108,14 +294,14 @@ impl Sync {
         for g in games {
             match (previous_release_dates.get(&g.id), &g.release_date) {
                 (Some(prev), Some(ref curr)) =>
-                    // TODO: Should consider moving the release notification to here instead,
-                    // as currently the actual release notifications will only affected noted
-                    // games, not wishlisted ones.
                     if prev != curr {
-                        let prev_date = conv::parse_release_date(&prev);
-                        let new_date = conv::parse_release_date(&curr);
+                        let prev_window = release_date::parse_release_window(prev);
+                        let new_window = release_date::parse_release_window(curr);
+                        let prev_date = prev_window.as_ref().and_then(|w| w.estimate());
+                        let new_date = new_window.as_ref().and_then(|w| w.estimate());
+                        let change = ReleaseChange::classify(prev_window.as_ref(), new_window.as_ref());
 
-                        self.repo.insert_release_update(&g.id, &prev, &prev_date, &curr, &new_date)
+                        self.repo.insert_release_update(&g.id, &prev, &prev_date, &curr, &new_date, change)
                             .await?;
 
                         let event = SyncEvent::ReleaseDateUpdated {
@@ -123,7 +309,8 @@ impl Sync {
                             prev_text: prev.clone(),
                             prev_date,
                             new_text: curr.clone(),
-                            new_date
+                            new_date,
+                            change,
                         };
                         updates.push(event);
                     }
@@ -135,62 +322,198 @@ impl Sync {
         Ok(updates)
     }
 
-    async fn sync_game_details(&mut self) -> Result<Vec<SyncEvent>> {
+    /// Check for tracked games which steam now says are released, having previously said they
+    /// weren't. Each game is only ever reported as released once.
+    async fn check_newly_released(&self, games: &[&GameDetails]) -> Result<Vec<SyncEvent>> {
+        println!("Checking for newly released games...");
+
+        let ids: Vec<&GameId> = games.iter().filter(|g| g.is_released).map(|&g| &g.id).collect();
+        let unreleased = self.repo.get_unreleased_games(&ids).await?;
+
+        let released: Vec<GameId> = ids.into_iter().filter(|id| unreleased.contains(id)).copied().collect();
+        let already_reported = self.repo.get_games_with_event("released", &released).await?;
+
+        Ok(
+            released
+                .into_iter()
+                .filter(|id| !already_reported.contains(id))
+                .map(|game| SyncEvent::Released { game })
+                .collect()
+        )
+    }
+
+    async fn sync_game_details(&mut self) -> Result<PhaseOutput> {
+        self.classify_release_updates().await?;
+
         let missing_games = self.repo.get_games_missing_details().await?;
+        let unnamed_games = self.repo.get_tracked_games_missing_names().await?;
         let noted_games = self.repo.get_upcoming_noted_game_ids().await?;
         let wishlisted_games = self.repo.get_upcoming_wishlisted_game_ids().await?;
+        let owned_games = self.repo.get_upcoming_owned_game_ids().await?;
 
         println!(
-            "Reading game details from steam. {} missing, upcoming: {} noted, {} wishlisted",
+            "Reading game details from steam. {} missing, {} unnamed, upcoming: {} noted, {} wishlisted, {} owned",
             &missing_games.len(),
+            &unnamed_games.len(),
             &noted_games.len(),
             &wishlisted_games.len(),
+            &owned_games.len(),
         );
 
         let refresh_ids: Vec<GameId> = {
             missing_games.into_iter()
+                .chain(unnamed_games)
                 .chain(noted_games.clone().into_iter())
                 .chain(wishlisted_games.clone().into_iter())
+                .chain(owned_games.clone())
+                .unique()
                 .collect()
         };
 
-        let (details, failures) = self.steam.get_game_details(&refresh_ids)?;
+        let (details, failures) = self.steam.get_game_details(&refresh_ids).await?;
 
-        // Collect the retrieved details for noted_games, we'll need them to check release changes
+        // Collect the retrieved details for upcoming games, we'll need them to check release changes
         let mut tracked_details = vec![];
         for d in &details {
-            if noted_games.contains(&d.id) || wishlisted_games.contains(&d.id) {
+            if noted_games.contains(&d.id) || wishlisted_games.contains(&d.id) || owned_games.contains(&d.id) {
                 tracked_details.push(d);
             }
         }
 
-        let events = match self.check_updated_release_dates(&tracked_details).await {
+        let mut events = match self.check_updated_release_dates(&tracked_details).await {
             Ok(evts) => evts,
             Err(e) => {
                 eprintln!("Failed to check for updated release dates: {}", e);
                 vec![]
             },
         };
+        // N.B. this has to happen before the new details are stored, to see what they replace
+        match self.check_newly_released(&tracked_details).await {
+            Ok(evts) => events.extend(evts),
+            Err(e) => eprintln!("Failed to check for newly released games: {}", e),
+        }
 
         self.repo.insert_game_details(&details).await?;
-        self.repo.mark_game_detail_failures(&failures).await;
-        Ok(events)
+        self.repo.update_game_metadata(&details).await?;
+
+        // The app list doesn't include every app, so fill in any names it's missing from details
+        let names: Vec<(GameId, String)> = {
+            details.iter().filter_map(|d| d.name.clone().map(|n| (d.id, n))).collect()
+        };
+        self.repo.insert_missing_names(&names).await?;
+
+        // Anything we've now got details for no longer needs backing off
+        let fetched_ids: Vec<GameId> = details.iter().map(|d| d.id).collect();
+        self.repo.delete_blacklist_entries(&fetched_ids).await?;
+
+        // Only count failures against a game when steam has nothing usable for it; anything
+        // transient will be tried again on the next sync without being blacklisted.
+        let permanent_failures: Vec<GameId> = {
+            failures
+                .iter()
+                .filter(|(_, f)| matches!(f, DetailsFailure::Permanent(_)))
+                .map(|(id, _)| *id)
+                .collect()
+        };
+        self.repo.mark_game_detail_failures(&permanent_failures, &Utc::now()).await;
+        self.skipped_details = failures;
+
+        Ok(PhaseOutput::with_events(details.len(), events))
+    }
+
+    /// Compare freshly-retrieved prices against those previously recorded to find games which
+    /// have gone on sale, hit their lowest recorded price, or dropped below a target price.
+    /// Sales and historical lows are only reported for wishlisted games, as those are the ones
+    /// we're waiting to buy; target prices are reported for any game with one set.
+    fn detect_price_events(
+        prices: &[GamePrice],
+        history: &HashMap<GameId, PriceHistory>,
+        targets: &HashMap<GameId, u32>,
+        wishlisted: &HashSet<GameId>,
+    ) -> Vec<SyncEvent> {
+        let mut events = vec![];
+
+        for p in prices {
+            let prev = history.get(&p.id);
+
+            if wishlisted.contains(&p.id) {
+                // Only report the start of a sale, rather than every sync while it's running
+                let newly_discounted = prev.map(|h| h.latest_discount_percent == 0).unwrap_or(true);
+                if p.discount_percent > 0 && newly_discounted {
+                    events.push(
+                        SyncEvent::OnSale {
+                            game: p.id,
+                            price: p.price,
+                            discount_percent: p.discount_percent,
+                        }
+                    );
+                }
+
+                if let Some(h) = prev.filter(|h| p.price < h.lowest_price) {
+                    events.push(
+                        SyncEvent::HistoricalLow { game: p.id, price: p.price, prev_low: h.lowest_price }
+                    );
+                }
+            }
+
+            if let Some(&target) = targets.get(&p.id) {
+                let newly_below = prev.map(|h| h.latest_price > target).unwrap_or(true);
+                if p.price <= target && newly_below {
+                    events.push(SyncEvent::BelowTargetPrice { game: p.id, price: p.price, target });
+                }
+            }
+        }
+
+        events
     }
 
-    async fn sync_wishlist(&mut self) -> Result<()> {
+    async fn sync_prices(&mut self) -> Result<PhaseOutput> {
+        let ids = self.repo.get_price_tracked_game_ids().await?;
+        println!("Reading prices from steam for {} wishlisted or noted games", ids.len());
+
+        let (prices, failures) = self.steam.get_prices(&ids).await?;
+        self.skipped_prices = failures;
+
+        // N.B. history must be read before inserting the new prices to compare against them
+        let history = self.repo.get_price_history(&ids).await?;
+        let targets = self.repo.get_target_prices().await?;
+        let wishlisted = self.repo.get_wishlisted_ids().await?;
+        let events = Self::detect_price_events(&prices, &history, &targets, &wishlisted);
+
+        self.repo.insert_game_prices(&prices).await?;
+        Ok(PhaseOutput::with_events(prices.len(), events))
+    }
+
+    async fn sync_wishlist(&mut self) -> Result<PhaseOutput> {
         let wishlist = self.steam.get_wishlist(&self.steam_account_id)?;
-        Ok(self.repo.update_wishlist(&wishlist).await?)
+        self.repo.update_wishlist(&wishlist).await?;
+
+        Ok(PhaseOutput::new(wishlist.len()))
     }
 
     pub async fn sync_steam(&mut self) -> Result<Vec<SyncEvent>> {
-        self.sync_steam_games().await?;
-        self.sync_owned_games().await?;
-        self.sync_wishlist().await?;
-        let events = self.sync_game_details().await?;
-        self.sync_played_games().await?;
+        let mut events = vec![];
 
+        for phase in SyncPhase::ALL.into_iter().filter(|p| *p != SyncPhase::Notion) {
+            events.extend(self.sync_phase(phase).await?);
+        }
         Ok(events)
     }
+
+    /// Run a single phase of the sync, recording its outcome against the current sync run
+    async fn sync_phase(&mut self, phase: SyncPhase) -> Result<Vec<SyncEvent>> {
+        let started = Utc::now();
+        let res = match phase {
+            SyncPhase::SteamGames => self.sync_steam_games().await,
+            SyncPhase::OwnedGames => self.sync_owned_games().await,
+            SyncPhase::Wishlist => self.sync_wishlist().await,
+            SyncPhase::GameDetails => self.sync_game_details().await,
+            SyncPhase::Prices => self.sync_prices().await,
+            SyncPhase::PlayedGames => self.sync_played_games().await,
+            SyncPhase::Notion => self.sync_notion_notes().await,
+        };
+        self.record_phase(phase, started, res).await
+    }
 }
 
 impl Sync {
@@ -209,6 +532,61 @@ impl Sync {
         Ok(())
     }
 
+    /// Search for fuzzy name matches for notes which couldn't be matched to a steam game by exact
+    /// name. Returns confident matches by name, to be applied in the same way as exact matches,
+    /// along with the candidates found, to be recorded for review with `rusteam match`.
+    async fn match_fuzzy_app_ids(
+        &self,
+        unmatched: &[&(String, String)]
+    ) -> Result<(HashMap<String, GameId>, Vec<AppIdCandidate>)> {
+        let mut matched: HashMap<String, GameId> = HashMap::new();
+        let mut candidates: Vec<AppIdCandidate> = vec![];
+
+        if unmatched.is_empty() {
+            return Ok((matched, candidates));
+        }
+
+        println!("Searching for fuzzy matches for {} notes without app IDs...", unmatched.len());
+
+        let index = NameIndex::new(self.repo.get_all_game_names().await?);
+        let rejected = self.repo.get_rejected_candidates().await?;
+
+        for (note_id, name) in unmatched {
+            let found: Vec<matching::MatchCandidate> = {
+                index
+                    .candidates(name, MAX_CANDIDATES * 2)
+                    .into_iter()
+                    .filter(|c| !rejected.contains(&(note_id.clone(), c.id)))
+                    .take(MAX_CANDIDATES)
+                    .collect()
+            };
+
+            let to_candidate = |c: &matching::MatchCandidate, status: CandidateStatus| {
+                AppIdCandidate {
+                    note_id: note_id.clone(),
+                    note_name: name.clone(),
+                    app_id: c.id,
+                    app_name: c.name.clone(),
+                    score: c.score,
+                    status,
+                }
+            };
+
+            match matching::confident_match(&found) {
+                Some(best) => {
+                    println!("Matched \"{}\" to \"{}\" ({})", name, &best.name, &best.id);
+                    matched.insert(name.clone(), best.id);
+                    candidates.push(to_candidate(best, CandidateStatus::Accepted));
+                },
+                None => {
+                    candidates.extend(found.iter().map(|c| to_candidate(c, CandidateStatus::Pending)));
+                },
+            }
+        }
+
+        Ok((matched, candidates))
+    }
+
     // Turn GameNote records, representing the data in notion, into NotedGame records, a slightly
     // different view of the data to store in postgres.
     // Fill in app IDs we've derived from postgres if they're missing in the notion data.
@@ -256,34 +634,88 @@ impl Sync {
             .collect()
     }
 
-    async fn update_release_states(&mut self) -> Result<Vec<SyncEvent>> {
+    /// Mark noted games as released in notion once steam says they're out. The Released events
+    /// themselves come from the game details sync, which covers every tracked game.
+    async fn update_release_states(&mut self) -> Result<()> {
         let updated_games = self.repo.get_newly_released_games().await?;
-        let mut events: Vec<SyncEvent> = vec![];
 
         for record in updated_games {
-            // FIXME: Use appid, let notifier resolve name
-            events.push(SyncEvent::Released { game: record.game_id.clone() });
-
+            println!("Marking {} as released in notion", &record.game_id);
             self.notion.set_state(&record.note_id, &GameState::Released)?;
         }
-        Ok(events)
+        Ok(())
     }
 
     pub async fn sync_notion(&mut self) -> Result<Vec<SyncEvent>> {
+        self.sync_phase(SyncPhase::Notion).await
+    }
+
+    async fn sync_notion_notes(&mut self) -> Result<PhaseOutput> {
         let notes = self.notion.get_notes().await?;
 
         let missing_app_ids = Self::missing_app_ids(&notes);
         let names: Vec<&str> = missing_app_ids.iter().map(|n| n.1.as_str()).collect();
-        let found_app_ids = self.repo.get_appids_by_name(&names).await?;
+        let mut found_app_ids = self.repo.get_appids_by_name(&names).await?;
+
+        // Fall back on fuzzy matching for anything we couldn't find an exact name match for
+        let unmatched: Vec<&(String, String)> = {
+            missing_app_ids.iter().filter(|(_, name)| !found_app_ids.contains_key(name)).collect()
+        };
+        let (fuzzy_app_ids, candidates) = self.match_fuzzy_app_ids(&unmatched).await?;
+        found_app_ids.extend(fuzzy_app_ids);
 
         let noted_games = Self::derive_noted_games(&notes, &found_app_ids);
 
         self.repo.insert_noted_games(&noted_games).await?;
+        self.repo.update_game_tags(&noted_games).await?;
+        self.repo.insert_app_id_candidates(&candidates).await?;
         self.write_app_ids_to_notion(&missing_app_ids, &found_app_ids)?;
 
-        // TODO: Populate game tags in postgres
-        // Try using fuzzy matching to look up app ids by fuzzy name search
-        // N.B. Postgres can do levenshtein directly, just need CREATE EXTENSION IF NOT EXISTS fuzzystrmatch
-        Ok(self.update_release_states().await?)
+        self.update_release_states().await?;
+        Ok(PhaseOutput::new(notes.len()))
+    }
+}
+
+impl Sync {
+    /// Run a full sync of steam and then notion, recording how it went in the sync_run table
+    pub async fn run(&mut self) -> Result<Vec<SyncEvent>> {
+        self.run_phases(&SyncPhase::ALL).await
+    }
+
+    /// Run the given phases of the sync as a single sync run, in their usual order
+    ///
+    /// Stops at the first phase which fails, leaving the rest for the next run. Events from the
+    /// phases which finished before then are still kept.
+    pub async fn run_phases(&mut self, phases: &[SyncPhase]) -> Result<Vec<SyncEvent>> {
+        let started = Utc::now();
+        self.run_id = match self.repo.start_sync_run(&started).await {
+            Ok(id) => Some(id),
+            Err(e) => {
+                eprintln!("Failed to record start of sync run: {}", e);
+                None
+            },
+        };
+
+        let res: Result<Vec<SyncEvent>> = async {
+            let mut events = vec![];
+            for phase in SyncPhase::ALL.into_iter().filter(|p| phases.contains(p)) {
+                events.extend(self.sync_phase(phase).await?);
+            }
+            Ok(events)
+        }.await;
+
+        if let Some(run_id) = self.run_id.take() {
+            let (status, error) = match &res {
+                Ok(_) => (SyncStatus::Succeeded, None),
+                Err(e) => (SyncStatus::Failed, Some(e.to_string())),
+            };
+
+            let finished = Utc::now();
+            if let Err(e) = self.repo.finish_sync_run(run_id, &finished, &status, error.as_deref()).await {
+                eprintln!("Failed to record end of sync run {}: {}", run_id, e);
+            }
+        }
+
+        res
     }
 }
@@ -0,0 +1,92 @@
+use super::*;
+
+use chrono::{TimeZone, Utc};
+
+fn price_fixture(app_id: u32, price: u32, discount_percent: u8) -> GamePrice {
+    GamePrice {
+        id: GameId { app_id },
+        price,
+        discount_percent,
+        recorded: Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap(),
+    }
+}
+
+fn history_fixture(app_id: u32, latest: u32, discount: u8, lowest: u32) -> (GameId, PriceHistory) {
+    (
+        GameId { app_id },
+        PriceHistory {
+            latest_price: latest,
+            latest_discount_percent: discount,
+            lowest_price: lowest,
+        }
+    )
+}
+
+fn wishlisted(ids: &[u32]) -> HashSet<GameId> {
+    ids.iter().map(|&app_id| GameId { app_id }).collect()
+}
+
+#[test]
+fn detect_price_events_new_sale() {
+    let prices = vec![price_fixture(666, 1499, 25)];
+    let history = HashMap::from([history_fixture(666, 1999, 0, 1499)]);
+
+    let expected = vec![
+        SyncEvent::OnSale { game: GameId { app_id: 666 }, price: 1499, discount_percent: 25 },
+    ];
+    let actual = Sync::detect_price_events(&prices, &history, &HashMap::new(), &wishlisted(&[666]));
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn detect_price_events_ongoing_sale() {
+    let prices = vec![price_fixture(666, 1499, 25)];
+    let history = HashMap::from([history_fixture(666, 1499, 25, 1499)]);
+
+    let actual = Sync::detect_price_events(&prices, &history, &HashMap::new(), &wishlisted(&[666]));
+
+    assert_eq!(actual, vec![]);
+}
+
+#[test]
+fn detect_price_events_historical_low() {
+    let prices = vec![price_fixture(666, 999, 50)];
+    let history = HashMap::from([history_fixture(666, 1999, 0, 1499)]);
+
+    let expected = vec![
+        SyncEvent::OnSale { game: GameId { app_id: 666 }, price: 999, discount_percent: 50 },
+        SyncEvent::HistoricalLow { game: GameId { app_id: 666 }, price: 999, prev_low: 1499 },
+    ];
+    let actual = Sync::detect_price_events(&prices, &history, &HashMap::new(), &wishlisted(&[666]));
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn detect_price_events_ignores_unwishlisted_sales() {
+    let prices = vec![price_fixture(1337, 999, 50)];
+    let history = HashMap::from([history_fixture(1337, 1999, 0, 1999)]);
+
+    let actual = Sync::detect_price_events(&prices, &history, &HashMap::new(), &wishlisted(&[666]));
+
+    assert_eq!(actual, vec![]);
+}
+
+#[test]
+fn detect_price_events_below_target() {
+    let prices = vec![price_fixture(1337, 999, 0), price_fixture(666, 999, 0)];
+    let history = HashMap::from([
+        history_fixture(1337, 1999, 0, 999),
+        history_fixture(666, 999, 0, 999),
+    ]);
+    let targets = HashMap::from([(GameId { app_id: 1337 }, 1000), (GameId { app_id: 666 }, 1000)]);
+
+    // 666 was already below target at the last sync, so shouldn't be reported again
+    let expected = vec![
+        SyncEvent::BelowTargetPrice { game: GameId { app_id: 1337 }, price: 999, target: 1000 },
+    ];
+    let actual = Sync::detect_price_events(&prices, &history, &targets, &wishlisted(&[]));
+
+    assert_eq!(actual, expected);
+}
@@ -0,0 +1,146 @@
+use std::path::Path;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use thiserror::Error;
+
+use crate::db::repo::{ReleaseUpdateHandling, Repo, RepoError, SteamGamesHandling, SyncEventHandling};
+use crate::db::sync::SyncEvent;
+use crate::models::game::GameId;
+
+// Namespace for entry IDs, which must never change once published
+const ID_PREFIX: &str = "tag:rusteam,2024";
+
+// Avoid the feed growing forever; readers will have seen anything older than this
+const MAX_ENTRIES: usize = 200;
+
+#[derive(Error, Debug)]
+pub enum FeedError {
+    #[error("Error reading feed entries from the database: {0}")]
+    Repo(#[from] RepoError),
+    #[error("Error writing feed: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, FeedError>;
+
+/// A single entry in the feed
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeedEntry {
+    pub id: String,
+    pub title: String,
+    pub link: String,
+    pub summary: String,
+    pub updated: DateTime<Utc>,
+}
+
+/// Escape text for inclusion in XML
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Render entries as an Atom feed document, newest first
+pub fn render_atom(title: &str, self_url: Option<&str>, entries: &[FeedEntry], now: &DateTime<Utc>) -> String {
+    let mut entries: Vec<&FeedEntry> = entries.iter().collect();
+    entries.sort_by(|a, b| b.updated.cmp(&a.updated).then(b.id.cmp(&a.id)));
+
+    // The feed only changes when an entry does, so readers can tell when there's nothing new
+    let updated = entries.first().map(|e| e.updated).unwrap_or(*now);
+
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    out.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    out.push_str(&format!("  <id>{}:feed</id>\n", ID_PREFIX));
+    out.push_str(&format!("  <title>{}</title>\n", escape_xml(title)));
+    out.push_str(&format!("  <updated>{}</updated>\n", updated.to_rfc3339()));
+    out.push_str("  <author><name>rusteam</name></author>\n");
+    if let Some(url) = self_url {
+        out.push_str(&format!("  <link rel=\"self\" href=\"{}\"/>\n", escape_xml(url)));
+    }
+
+    for e in entries {
+        out.push_str("  <entry>\n");
+        out.push_str(&format!("    <id>{}</id>\n", escape_xml(&e.id)));
+        out.push_str(&format!("    <title>{}</title>\n", escape_xml(&e.title)));
+        out.push_str(&format!("    <link href=\"{}\"/>\n", escape_xml(&e.link)));
+        out.push_str(&format!("    <updated>{}</updated>\n", e.updated.to_rfc3339()));
+        out.push_str(&format!("    <summary>{}</summary>\n", escape_xml(&e.summary)));
+        out.push_str("  </entry>\n");
+    }
+
+    out.push_str("</feed>\n");
+    out
+}
+
+/// Collect release date changes and newly released games since the given time as feed entries
+pub async fn feed_entries(repo: &Repo, since: &DateTime<Utc>) -> Result<Vec<FeedEntry>> {
+    let mut entries = vec![];
+
+    for u in repo.get_release_updates_since(since).await? {
+        let name = u.name.unwrap_or(u.game.to_string());
+        let prev_text = u.prev_text.unwrap_or_default();
+        let new_text = u.new_text.unwrap_or_default();
+
+        entries.push(
+            FeedEntry {
+                id: format!("{}:release_update/{}", ID_PREFIX, u.id),
+                title: format!("{}: {}", u.change.label(), &name),
+                link: u.game.store_url(),
+                summary: format!("Release date for {} changed from \"{}\" to \"{}\"", &name, prev_text, new_text),
+                updated: u.recorded,
+            }
+        );
+    }
+
+    // Releases are only recorded as sync events
+    let released: Vec<_> = {
+        repo.get_sync_events_since(since)
+            .await?
+            .into_iter()
+            .filter(|e| matches!(e.event, SyncEvent::Released { .. }))
+            .collect()
+    };
+    let ids: Vec<GameId> = released.iter().map(|e| *e.event.game()).collect();
+    let names_by_id = repo.get_game_names_by_id(&ids).await?;
+
+    for e in released {
+        let game = e.event.game();
+        let name = names_by_id.get(game).cloned().unwrap_or(game.to_string());
+
+        entries.push(
+            FeedEntry {
+                id: format!("{}:sync_event/{}", ID_PREFIX, e.id),
+                title: format!("🚀 {} is out now", &name),
+                link: game.store_url(),
+                summary: e.event.describe(&name),
+                updated: e.recorded,
+            }
+        );
+    }
+
+    entries.sort_by_key(|e| std::cmp::Reverse(e.updated));
+    entries.truncate(MAX_ENTRIES);
+    Ok(entries)
+}
+
+/// Write an Atom feed of the last `days` of release news to the given path
+pub async fn write_feed(
+    repo: &Repo,
+    path: &Path,
+    title: &str,
+    self_url: Option<&str>,
+    days: i64
+) -> Result<usize> {
+    let now = Utc::now();
+    let entries = feed_entries(repo, &(now - TimeDelta::days(days))).await?;
+
+    // Write alongside and then move into place, so a web server never serves half a feed
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, render_atom(title, self_url, &entries, &now))?;
+    std::fs::rename(&tmp, path)?;
+
+    Ok(entries.len())
+}
@@ -1,7 +1,12 @@
 #![allow(async_fn_in_trait)]
 pub mod cli;
 pub mod config;
+pub mod daemon;
 pub mod db;
+pub mod feed;
+pub mod matching;
 pub mod models;
+pub mod notify;
 pub mod notion;
+pub mod reputation;
 pub mod steam;
@@ -0,0 +1,125 @@
+#[cfg(test)]
+mod tests;
+
+use std::cmp::Ordering;
+
+use strsim::normalized_levenshtein;
+
+use crate::models::game::GameId;
+
+/// Minimum score for a fuzzy match to be applied without asking first
+pub const AUTO_MATCH_THRESHOLD: f64 = 0.95;
+
+/// How far a fuzzy match must score above the runner-up to be applied without asking first
+pub const MIN_MATCH_MARGIN: f64 = 0.05;
+
+/// Minimum score for a candidate to be worth suggesting at all
+pub const MIN_CANDIDATE_SCORE: f64 = 0.6;
+
+/// Maximum number of candidates to record for review for each unmatched name
+pub const MAX_CANDIDATES: usize = 5;
+
+// Matches on just the title, ignoring a subtitle on one side or the other, are less certain
+const SUBTITLE_PENALTY: f64 = 0.9;
+
+const SUBTITLE_SEPARATORS: [&str; 3] = [":", " - ", " – "];
+
+/// A steam game which may match a name we couldn't find an exact match for
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchCandidate {
+    pub id: GameId,
+    pub name: String,
+    pub score: f64,
+}
+
+// Pre-normalised game name, along with its title with any subtitle stripped
+struct IndexEntry {
+    id: GameId,
+    name: String,
+    normalised: String,
+    title: Option<String>,
+}
+
+/// Index of steam game names to search for fuzzy matches. Names are normalised up front as
+/// there are a great many steam games to compare against.
+pub struct NameIndex {
+    entries: Vec<IndexEntry>,
+}
+
+/// Normalise a game name for comparison: lowercase, drop trademark symbols and punctuation,
+/// and collapse whitespace, so e.g. "Baldur's Gate™ 3" and "Baldurs Gate 3" compare equal
+pub fn normalise_name(name: &str) -> String {
+    name
+        .replace('&', " and ")
+        .chars()
+        .filter(|c| !matches!(c, '™' | '®' | '©' | '\'' | '’'))
+        .map(|c| if c.is_alphanumeric() { c.to_lowercase().next().unwrap_or(c) } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+// Get the normalised title of a name with a subtitle, like "Title: Subtitle" or "Title - Subtitle"
+fn strip_subtitle(name: &str) -> Option<String> {
+    SUBTITLE_SEPARATORS
+        .iter()
+        .filter_map(|sep| name.split_once(sep))
+        .map(|(title, _)| normalise_name(title))
+        .find(|title| !title.is_empty())
+}
+
+impl NameIndex {
+    pub fn new(games: Vec<(GameId, String)>) -> NameIndex {
+        let entries = games
+            .into_iter()
+            .map(|(id, name)| {
+                IndexEntry {
+                    id,
+                    normalised: normalise_name(&name),
+                    title: strip_subtitle(&name),
+                    name,
+                }
+            })
+            .collect();
+
+        NameIndex { entries }
+    }
+
+    /// Find the best-scoring candidates for a name, best first. Scores are between 0 and 1, and
+    /// anything scoring below MIN_CANDIDATE_SCORE is left out.
+    pub fn candidates(&self, name: &str, limit: usize) -> Vec<MatchCandidate> {
+        let normalised = normalise_name(name);
+        let title = strip_subtitle(name);
+
+        let mut scored: Vec<MatchCandidate> = self.entries
+            .iter()
+            .map(|e| {
+                let mut score = normalized_levenshtein(&normalised, &e.normalised);
+
+                if e.title.as_ref() == Some(&normalised) || title.as_ref() == Some(&e.normalised) {
+                    score = score.max(SUBTITLE_PENALTY);
+                }
+
+                MatchCandidate { id: e.id, name: e.name.clone(), score }
+            })
+            .filter(|c| c.score >= MIN_CANDIDATE_SCORE)
+            .collect();
+
+        scored.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
+        scored.truncate(limit);
+        scored
+    }
+}
+
+/// Pick a candidate which is good enough to apply automatically, if any. It must score above
+/// the threshold and beat the runner-up by at least `MIN_MATCH_MARGIN`, so we don't guess
+/// between remakes or editions which share a name.
+pub fn confident_match(candidates: &[MatchCandidate]) -> Option<&MatchCandidate> {
+    let best = candidates.first().filter(|c| c.score >= AUTO_MATCH_THRESHOLD)?;
+
+    match candidates.get(1) {
+        Some(runner_up) if best.score - runner_up.score < MIN_MATCH_MARGIN => None,
+        _ => Some(best),
+    }
+}
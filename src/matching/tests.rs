@@ -0,0 +1,93 @@
+use super::*;
+
+fn index_fixture() -> NameIndex {
+    NameIndex::new(
+        vec![
+            (GameId { app_id: 666 }, "Game Buying Simulator 2024".to_string()),
+            (GameId { app_id: 1337 }, "Final Fantasy MMLXVII".to_string()),
+            (GameId { app_id: 654321 }, "Paint Drying Tycoon 2".to_string()),
+            (GameId { app_id: 666666 }, "Unearthed Myths 7: The Unearthening".to_string()),
+            (GameId { app_id: 777 }, "DOOM".to_string()),
+            (GameId { app_id: 778 }, "Doom".to_string()),
+        ]
+    )
+}
+
+#[test]
+fn normalise_name_punctuation_and_symbols() {
+    let inputs = vec![
+        ("Baldur's Gate™ 3", "baldurs gate 3"),
+        ("  Papers,   Please ", "papers please"),
+        ("Ratchet & Clank", "ratchet and clank"),
+        ("S.T.A.L.K.E.R. 2", "s t a l k e r 2"),
+        ("DOOM®", "doom"),
+    ];
+
+    for (input, expected) in inputs {
+        assert_eq!(normalise_name(input), expected, "should normalise \"{}\" correctly", input);
+    }
+}
+
+#[test]
+fn candidates_exact_after_normalising() {
+    let index = index_fixture();
+    let actual = index.candidates("Final Fantasy MMLXVII™", 3);
+
+    assert_eq!(actual[0].id, GameId { app_id: 1337 });
+    assert_eq!(actual[0].score, 1.0);
+    assert_eq!(actual.len(), 1);
+}
+
+#[test]
+fn candidates_limited() {
+    let index = index_fixture();
+    let actual = index.candidates("Doom", 1);
+
+    assert_eq!(actual.len(), 1);
+    assert_eq!(actual[0].score, 1.0);
+}
+
+#[test]
+fn candidates_missing_subtitle() {
+    let index = index_fixture();
+    let actual = index.candidates("Unearthed Myths 7", MAX_CANDIDATES);
+
+    assert_eq!(actual[0].id, GameId { app_id: 666666 });
+    assert_eq!(actual[0].score, SUBTITLE_PENALTY);
+}
+
+#[test]
+fn confident_match_applies_clear_winner() {
+    let index = index_fixture();
+    let candidates = index.candidates("Paint Drying Tycoon 2™", MAX_CANDIDATES);
+
+    assert_eq!(confident_match(&candidates).map(|c| c.id), Some(GameId { app_id: 654321 }));
+}
+
+#[test]
+fn confident_match_rejects_subtitle_match() {
+    let index = index_fixture();
+    let candidates = index.candidates("Unearthed Myths 7", MAX_CANDIDATES);
+
+    assert_eq!(confident_match(&candidates), None);
+}
+
+#[test]
+fn confident_match_rejects_ties() {
+    let index = index_fixture();
+    let candidates = index.candidates("Doom", MAX_CANDIDATES);
+
+    assert_eq!(candidates[0].score, 1.0);
+    assert_eq!(confident_match(&candidates), None);
+}
+
+#[test]
+fn confident_match_rejects_close_runner_up() {
+    let candidate = |app_id: u32, score: f64| MatchCandidate { id: GameId { app_id }, name: "Doom".to_string(), score };
+
+    let close = vec![candidate(777, 0.99), candidate(778, 0.96)];
+    assert_eq!(confident_match(&close), None);
+
+    let clear = vec![candidate(777, 0.99), candidate(778, 0.90)];
+    assert_eq!(confident_match(&clear).map(|c| c.id), Some(GameId { app_id: 777 }));
+}
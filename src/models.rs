@@ -1,3 +1,4 @@
 pub mod game;
 pub mod notion;
 pub mod steam;
+pub mod sync;
@@ -3,10 +3,11 @@ use std::fmt;
 use std::hash::Hash;
 use std::time::Duration;
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, TimeDelta, Utc};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+
 #[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct GameId {
     pub app_id: u32
@@ -45,6 +46,18 @@ impl Into<i64> for GameId {
         self.app_id.into()
     }
 }
+impl GameId {
+    /// Link to the game's page in the steam store
+    pub fn store_url(&self) -> String {
+        format!("https://store.steampowered.com/app/{}", self.app_id)
+    }
+
+    /// Link to the banner image shown at the top of the game's store page
+    pub fn header_image_url(&self) -> String {
+        format!("https://cdn.akamai.steamstatic.com/steam/apps/{}/header.jpg", self.app_id)
+    }
+}
+
 impl fmt::Display for GameId {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", &self.app_id)
@@ -116,6 +129,56 @@ pub struct NotedGame {
     pub first_noted: DateTime<Utc>,
 }
 
+/// The kinds of app steam lists separately in its app catalogue
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum AppType {
+    Game,
+    Dlc,
+    Software,
+    Video,
+    Hardware,
+}
+
+impl AppType {
+    pub const ALL: [AppType; 5] = [
+        AppType::Game,
+        AppType::Dlc,
+        AppType::Software,
+        AppType::Video,
+        AppType::Hardware,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AppType::Game => "game",
+            AppType::Dlc => "dlc",
+            AppType::Software => "software",
+            AppType::Video => "video",
+            AppType::Hardware => "hardware",
+        }
+    }
+}
+
+impl TryFrom<&str> for AppType {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        AppType::ALL
+            .into_iter()
+            .find(|t| t.as_str() == s)
+            .ok_or(format!("Unknown app type: {}", s))
+    }
+}
+
+/// Represents an app in steam's catalogue, i.e. a record in the steam_game table
+#[derive(Clone, Debug, PartialEq)]
+pub struct SteamApp {
+    pub id: GameId,
+    pub name: String,
+    pub app_type: AppType,
+    pub last_modified: DateTime<Utc>,
+}
+
 /// Represents a cleaner / simplified version of SteamOwnedGame to hold playtime details
 // TODO: Consider replacing SteamOwnedGame with this model and deserialising directly into it
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
@@ -147,7 +210,13 @@ pub struct WishlistedGame {
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct GameDetails {
     pub id: GameId,
+    // Not stored in game_details, but used to fill in names missing from steam_game
+    pub name: Option<String>,
+    // As given by appdetails, e.g. "game", "dlc" or "demo"
+    pub app_type: Option<String>,
+    pub is_free: Option<bool>,
     pub description: Option<String>,
+    pub header_image: Option<String>,
     pub controller_support: Option<String>,
     pub coop: bool,
     pub local_coop: bool,
@@ -155,9 +224,383 @@ pub struct GameDetails {
     pub is_released: bool,
     pub release_date: Option<String>,
     pub release_estimate: Option<DateTime<Utc>>,
+    // Stored as release_earliest, release_latest and release_precision
+    pub release_window: Option<ReleaseWindow>,
+    // Stored in the game_category, game_genre, game_company and game_platform tables
+    pub categories: Vec<Category>,
+    pub genres: Vec<Genre>,
+    pub developers: Vec<String>,
+    pub publishers: Vec<String>,
+    pub platforms: Vec<Platform>,
     pub recorded: DateTime<Utc>,
 }
 
+/// How precisely a store page pins down when a game will be released
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum ReleasePrecision {
+    Day,
+    Month,
+    Quarter,
+    Half,
+    Year,
+    Unknown,
+}
+
+impl ReleasePrecision {
+    pub const ALL: [ReleasePrecision; 6] = [
+        ReleasePrecision::Day,
+        ReleasePrecision::Month,
+        ReleasePrecision::Quarter,
+        ReleasePrecision::Half,
+        ReleasePrecision::Year,
+        ReleasePrecision::Unknown,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReleasePrecision::Day => "day",
+            ReleasePrecision::Month => "month",
+            ReleasePrecision::Quarter => "quarter",
+            ReleasePrecision::Half => "half",
+            ReleasePrecision::Year => "year",
+            ReleasePrecision::Unknown => "unknown",
+        }
+    }
+}
+
+impl TryFrom<&str> for ReleasePrecision {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        ReleasePrecision::ALL
+            .into_iter()
+            .find(|p| p.as_str() == s)
+            .ok_or(format!("Unknown release precision: {}", s))
+    }
+}
+
+/// The period a game's release date text says it'll be released in, e.g. "Q2 2025" is any time
+/// from the 1st of April up to, but not including, the 1st of July
+///
+/// Both bounds are missing when the date is unknown, like "To be announced".
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ReleaseWindow {
+    pub earliest: Option<DateTime<Utc>>,
+    pub latest: Option<DateTime<Utc>>,
+    pub precision: ReleasePrecision,
+}
+
+impl ReleaseWindow {
+    pub fn unknown() -> ReleaseWindow {
+        ReleaseWindow { earliest: None, latest: None, precision: ReleasePrecision::Unknown }
+    }
+
+    /// A single date to sort and compare releases by: the day itself for exact dates, otherwise
+    /// the end of the window, as the latest the game could be released without a delay
+    pub fn estimate(&self) -> Option<DateTime<Utc>> {
+        match self.precision {
+            ReleasePrecision::Day => self.earliest,
+            _ => self.latest,
+        }
+    }
+}
+
+/// How a game's release date moved when its text changed, as recorded in release_update
+#[derive(Copy, Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReleaseChange {
+    /// The window ends later than it did, without just being widened
+    Postponed,
+    /// The window ends earlier than it did, without just being narrowed
+    BroughtForward,
+    /// More precise, within the previous window, e.g. "2026" -> "Q2 2026"
+    Narrowed,
+    /// Less precise, around the previous window, e.g. "Q2 2026" -> "2026"
+    Widened,
+    /// Had a date but has gone back to "To be announced", "Coming soon" or similar
+    Tba,
+    /// Anything else, e.g. the same date written differently
+    #[default]
+    Changed,
+}
+
+impl ReleaseChange {
+    pub const ALL: [ReleaseChange; 6] = [
+        ReleaseChange::Postponed,
+        ReleaseChange::BroughtForward,
+        ReleaseChange::Narrowed,
+        ReleaseChange::Widened,
+        ReleaseChange::Tba,
+        ReleaseChange::Changed,
+    ];
+
+    /// Compare the windows given by the previous and new release date text
+    ///
+    /// Text which couldn't be parsed says nothing about which way the date moved, so is only
+    /// ever a plain change, never back to TBA.
+    pub fn classify(prev: Option<&ReleaseWindow>, new: Option<&ReleaseWindow>) -> ReleaseChange {
+        let (Some(prev), Some(new)) = (prev, new) else { return ReleaseChange::Changed };
+
+        match (prev.earliest.zip(prev.latest), new.earliest.zip(new.latest)) {
+            (Some(p), Some(n)) if p == n => ReleaseChange::Changed,
+            (Some((pe, pl)), Some((ne, nl))) => {
+                if ne >= pe && nl <= pl {
+                    ReleaseChange::Narrowed
+                } else if ne <= pe && nl >= pl {
+                    ReleaseChange::Widened
+                } else if nl > pl {
+                    ReleaseChange::Postponed
+                } else {
+                    ReleaseChange::BroughtForward
+                }
+            },
+            (Some(_), None) => ReleaseChange::Tba,
+            (None, Some(_)) => ReleaseChange::Narrowed,
+            (None, None) => ReleaseChange::Changed,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReleaseChange::Postponed => "postponed",
+            ReleaseChange::BroughtForward => "brought_forward",
+            ReleaseChange::Narrowed => "narrowed",
+            ReleaseChange::Widened => "widened",
+            ReleaseChange::Tba => "tba",
+            ReleaseChange::Changed => "changed",
+        }
+    }
+
+    /// Whether the change pushed the release back, or made it less certain
+    /// N.B. this is mirrored in the queries behind the reputation report
+    pub fn is_delay(&self) -> bool {
+        matches!(self, ReleaseChange::Postponed | ReleaseChange::Widened | ReleaseChange::Tba)
+    }
+
+    /// Short heading for the change, as used in notifications and the feed
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReleaseChange::Postponed => "🐌 Delayed",
+            ReleaseChange::BroughtForward => "⏩ Brought forward",
+            ReleaseChange::Narrowed => "📌 Narrowed down",
+            ReleaseChange::Widened => "🌫️ Less certain",
+            ReleaseChange::Tba => "❓ Back to TBA",
+            ReleaseChange::Changed => "🔎 Changed",
+        }
+    }
+}
+
+impl TryFrom<&str> for ReleaseChange {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        ReleaseChange::ALL
+            .into_iter()
+            .find(|c| c.as_str() == s)
+            .ok_or(format!("Unknown release change: {}", s))
+    }
+}
+
+/// A distinct release date text in the game_details table, and the games which have it
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReleaseDateText {
+    pub text: String,
+    pub game_count: i64,
+    pub example: GameId,
+    pub example_name: Option<String>,
+}
+
+/// Bumped whenever update_game_metadata starts storing more about each game, so details recorded
+/// before then are fetched again to fill it in. Version 1 added genres, companies and platforms,
+/// and version 2 categories.
+pub const GAME_METADATA_VERSION: i16 = 2;
+
+/// Represents a record in the category table
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Category {
+    pub id: u32,
+    pub description: String,
+}
+
+/// Represents a record in the genre table
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Genre {
+    pub id: u32,
+    pub description: String,
+}
+
+/// Platforms a game can be played on, as recorded in the game_platform table
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum Platform {
+    Windows,
+    Mac,
+    Linux,
+}
+
+impl Platform {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Platform::Windows => "windows",
+            Platform::Mac => "mac",
+            Platform::Linux => "linux",
+        }
+    }
+}
+
+/// Represents a record in the game_price table. Prices are in pence.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct GamePrice {
+    pub id: GameId,
+    pub price: u32,
+    pub discount_percent: u8,
+    pub recorded: DateTime<Utc>,
+}
+
+/// Summary of the prices previously recorded for a game in the game_price table
+#[derive(Clone, Debug, PartialEq)]
+pub struct PriceHistory {
+    pub latest_price: u32,
+    pub latest_discount_percent: u8,
+    pub lowest_price: u32,
+}
+
+/// Format a price in pence as pounds for display, e.g. 999 -> "£9.99"
+pub fn format_price(pence: u32) -> String {
+    format!("£{}.{:02}", pence / 100, pence % 100)
+}
+
+/// Parse a user-provided price in pounds, like "9.99" or "£10", into pence
+pub fn parse_price(s: &str) -> Option<u32> {
+    let clean = s.trim().trim_start_matches('£');
+    let (pounds, pence) = match clean.split_once('.') {
+        Some((pounds, pence)) if pence.len() == 2 => (pounds, pence.parse::<u32>().ok()?),
+        Some((pounds, pence)) if pence.len() == 1 => (pounds, pence.parse::<u32>().ok()? * 10),
+        Some(_) => return None,
+        None => (clean, 0),
+    };
+
+    pounds.parse::<u32>().ok()?.checked_mul(100)?.checked_add(pence)
+}
+
+/// Review status of a fuzzy-matched app ID candidate for a note
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub enum CandidateStatus {
+    Pending,
+    Accepted,
+    Rejected,
+}
+
+impl From<String> for CandidateStatus {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "accepted" => CandidateStatus::Accepted,
+            "rejected" => CandidateStatus::Rejected,
+            _ => CandidateStatus::Pending,
+        }
+    }
+}
+
+impl From<CandidateStatus> for String {
+    fn from(status: CandidateStatus) -> Self {
+        match status {
+            CandidateStatus::Pending => "pending".to_string(),
+            CandidateStatus::Accepted => "accepted".to_string(),
+            CandidateStatus::Rejected => "rejected".to_string(),
+        }
+    }
+}
+
+/// Represents a record in the app_id_candidate table: a possible steam game for a note which
+/// couldn't be matched to one by exact name
+#[derive(Clone, Debug, PartialEq)]
+pub struct AppIdCandidate {
+    pub note_id: String,
+    pub note_name: String,
+    pub app_id: GameId,
+    pub app_name: String,
+    pub score: f64,
+    pub status: CandidateStatus,
+}
+
+/// Number of failures after which we stop trying to get details for a game until it's reset
+pub const BLACKLIST_MAX_FAILURES: u32 = 5;
+/// Time to wait before retrying a game after its first failure, doubled with each further failure
+pub const BLACKLIST_BACKOFF_HOURS: u32 = 6;
+
+/// Represents a record in the game_details_blacklist table: a game for which steam hasn't
+/// returned a usable definition
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlacklistedGame {
+    pub id: GameId,
+    pub name: Option<String>,
+    pub failure_count: u32,
+    pub last_failed: Option<DateTime<Utc>>,
+}
+
+impl BlacklistedGame {
+    /// Whether we've given up on getting details for this game
+    pub fn is_retired(&self) -> bool {
+        self.failure_count >= BLACKLIST_MAX_FAILURES
+    }
+
+    /// When details can next be requested for this game, or None if it's been retired.
+    /// N.B. this mirrors the check in get_games_missing_details
+    pub fn retry_after(&self) -> Option<DateTime<Utc>> {
+        if self.is_retired() {
+            return None;
+        }
+
+        let backoff_hours = {
+            BLACKLIST_BACKOFF_HOURS as i64 * 2_i64.pow(self.failure_count.saturating_sub(1))
+        };
+
+        Some(
+            self.last_failed
+                .map(|t| t + TimeDelta::hours(backoff_hours))
+                .unwrap_or(DateTime::<Utc>::MIN_UTC)
+        )
+    }
+}
+
+/// Represents a record in the release_update table: a change to a game's release date text
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReleaseUpdate {
+    pub id: i32,
+    pub game: GameId,
+    pub name: Option<String>,
+    pub prev_text: Option<String>,
+    pub new_text: Option<String>,
+    pub prev_estimate: Option<DateTime<Utc>>,
+    pub new_estimate: Option<DateTime<Utc>>,
+    pub change: ReleaseChange,
+    pub recorded: DateTime<Utc>,
+}
+
+/// A release date change for a game, attributed to one of the studios behind it, or just the
+/// game itself if its release date has never changed
+#[derive(Clone, Debug, PartialEq)]
+pub struct StudioReleaseUpdate {
+    pub studio: String,
+    // "developer" or "publisher", as stored in game_company
+    pub role: String,
+    pub game: GameId,
+    pub change: Option<ReleaseChange>,
+    pub prev_estimate: Option<DateTime<Utc>>,
+    pub new_estimate: Option<DateTime<Utc>>,
+}
+
+/// An unreleased game on the wishlist, with the studios behind it and how often it's slipped
+#[derive(Clone, Debug, PartialEq)]
+pub struct UpcomingGame {
+    pub id: GameId,
+    pub name: Option<String>,
+    pub release_date: Option<String>,
+    pub release_estimate: Option<DateTime<Utc>>,
+    pub developers: Vec<String>,
+    pub publishers: Vec<String>,
+    pub delays: i64,
+}
+
 /// Represents details of a game newly released and in need of update in notion
 #[derive(Debug)]
 pub struct ReleasedGame {
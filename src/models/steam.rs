@@ -3,7 +3,7 @@ mod tests;
 
 use std::collections::HashMap;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct SteamOwnedGame {
@@ -24,19 +24,25 @@ pub struct SteamOwnedGamesResponse {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-pub struct SteamAllGamesResponse {
-    pub applist: SteamApplist,
+pub struct SteamStoreAppListResponse {
+    pub response: SteamStoreAppList,
 }
 
+/// A page of IStoreService/GetAppList results; `last_appid` is where the next page starts
 #[derive(Clone, Debug, Deserialize)]
-pub struct SteamApplist {
-    pub apps: Vec<SteamAppIdPair>,
+pub struct SteamStoreAppList {
+    #[serde(default)]
+    pub apps: Vec<SteamStoreApp>,
+    #[serde(default)]
+    pub have_more_results: bool,
+    pub last_appid: Option<u32>,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
-pub struct SteamAppIdPair {
+pub struct SteamStoreApp {
     pub appid: u32,
-    pub name: String
+    pub name: String,
+    pub last_modified: i64,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
@@ -52,13 +58,46 @@ pub struct SteamAppDetailsResponseEntry {
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 pub struct SteamAppDetails {
+    #[serde(rename = "type")]
+    pub app_type: Option<String>,
+    pub name: Option<String>,
+    pub is_free: Option<bool>,
     pub short_description: Option<String>,
+    pub header_image: Option<String>,
     pub controller_support: Option<String>,
-    pub categories: Vec<Category>,
+    #[serde(default)]
+    pub developers: Vec<String>,
+    #[serde(default)]
+    pub publishers: Vec<String>,
+    pub platforms: Option<Platforms>,
+    pub categories: Vec<SteamCategory>,
+    #[serde(default)]
+    pub genres: Vec<SteamGenre>,
     pub metacritic: Option<MetacriticScore>,
     pub release_date: Option<ReleaseDate>,
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(transparent)]
+pub struct SteamPriceOverviewResponse {
+    pub results: HashMap<String, SteamPriceOverviewResponseEntry>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct SteamPriceOverviewResponseEntry {
+    pub success: bool,
+    pub data: Option<SteamPriceDetails>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum SteamPriceDetails {
+    Priced { price_overview: PriceOverview },
+    // Steam gives an empty array instead of an object for games without a price, i.e. those
+    // which are free or not yet purchasable
+    Unpriced(Vec<serde_json::Value>),
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct SteamWishlistResponse {
     pub response: SteamWishlistedGames,
@@ -75,12 +114,29 @@ pub struct SteamWishlistItem {
     pub date_added: i64,
 }
 
-#[derive(Clone, Debug, Deserialize, PartialEq)]
-pub struct Category {
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SteamCategory {
     pub id: u32,
     pub description: String
 }
 
+/// Unlike categories, genre IDs are given as strings
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct SteamGenre {
+    pub id: String,
+    pub description: String
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct Platforms {
+    #[serde(default)]
+    pub windows: bool,
+    #[serde(default)]
+    pub mac: bool,
+    #[serde(default)]
+    pub linux: bool,
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 pub struct ReleaseDate {
     pub coming_soon: bool,
@@ -91,3 +147,13 @@ pub struct ReleaseDate {
 pub struct MetacriticScore {
     pub score: u8
 }
+
+/// Prices are given in the smallest unit of the currency, i.e. pence
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct PriceOverview {
+    pub currency: String,
+    pub initial: u32,
+    #[serde(rename = "final")]
+    pub final_price: u32,
+    pub discount_percent: u8,
+}
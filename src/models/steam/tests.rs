@@ -11,13 +11,27 @@ fn read_steam_app_details_response() {
     // Real API response for Stardew Valley, but some fields truncated for easier testing
     let expected_entry = SteamAppDetailsResponseEntry {
         data: SteamAppDetails {
+            app_type: Some("game".to_string()),
+            name: Some("Stardew Valley".to_string()),
+            is_free: Some(false),
             short_description: Some("SHORT DESC".to_string()),
+            header_image: Some(
+                "https://cdn.akamai.steamstatic.com/steam/apps/413150/header.jpg?t=1710874434".to_string()
+            ),
             controller_support: Some("full".to_string()),
+            developers: vec!["ConcernedApe".to_string()],
+            publishers: vec!["ConcernedApe".to_string()],
+            platforms: Some(Platforms { windows: true, mac: true, linux: true }),
             categories: vec![
-                Category { id: 2, description: "Single-player".to_string() },
-                Category { id: 1, description: "Multi-player".to_string() },
-                Category { id: 9, description: "Co-op".to_string() },
-                Category { id: 38, description: "Online Co-op".to_string() },
+                SteamCategory { id: 2, description: "Single-player".to_string() },
+                SteamCategory { id: 1, description: "Multi-player".to_string() },
+                SteamCategory { id: 9, description: "Co-op".to_string() },
+                SteamCategory { id: 38, description: "Online Co-op".to_string() },
+            ],
+            genres: vec![
+                SteamGenre { id: "23".to_string(), description: "Indie".to_string() },
+                SteamGenre { id: "3".to_string(), description: "RPG".to_string() },
+                SteamGenre { id: "28".to_string(), description: "Simulation".to_string() },
             ],
             metacritic: Some(MetacriticScore { score: 89 }),
             release_date: Some(ReleaseDate { coming_soon: false, date: "26 Feb, 2016".to_string() }),
@@ -32,3 +46,59 @@ fn read_steam_app_details_response() {
 
     assert_eq!(actual, expected);
 }
+
+#[test]
+fn read_steam_price_overview_response() {
+    let data = r#"{
+        "666": {
+            "success": true,
+            "data": {
+                "price_overview": {
+                    "currency": "GBP",
+                    "initial": 1999,
+                    "final": 999,
+                    "discount_percent": 50,
+                    "initial_formatted": "£19.99",
+                    "final_formatted": "£9.99"
+                }
+            }
+        },
+        "1337": { "success": true, "data": [] },
+        "654321": { "success": false }
+    }"#;
+
+    let expected_entries: HashMap<String, SteamPriceOverviewResponseEntry> = HashMap::from([
+        (
+            "666".to_string(),
+            SteamPriceOverviewResponseEntry {
+                success: true,
+                data: Some(
+                    SteamPriceDetails::Priced {
+                        price_overview: PriceOverview {
+                            currency: "GBP".to_string(),
+                            initial: 1999,
+                            final_price: 999,
+                            discount_percent: 50,
+                        }
+                    }
+                ),
+            }
+        ),
+        (
+            "1337".to_string(),
+            SteamPriceOverviewResponseEntry {
+                success: true,
+                data: Some(SteamPriceDetails::Unpriced(vec![])),
+            }
+        ),
+        (
+            "654321".to_string(),
+            SteamPriceOverviewResponseEntry { success: false, data: None }
+        ),
+    ]);
+
+    let expected = SteamPriceOverviewResponse { results: expected_entries };
+    let actual: SteamPriceOverviewResponse = serde_json::from_str(data).unwrap();
+
+    assert_eq!(actual, expected);
+}
@@ -0,0 +1,118 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+
+/// A distinct stage of the sync process, recorded separately in sync_run_phase
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum SyncPhase {
+    SteamGames,
+    OwnedGames,
+    Wishlist,
+    GameDetails,
+    Prices,
+    PlayedGames,
+    Notion,
+}
+
+impl SyncPhase {
+    pub const ALL: [SyncPhase; 7] = [
+        SyncPhase::SteamGames,
+        SyncPhase::OwnedGames,
+        SyncPhase::Wishlist,
+        SyncPhase::GameDetails,
+        SyncPhase::Prices,
+        SyncPhase::PlayedGames,
+        SyncPhase::Notion,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyncPhase::SteamGames => "steam_games",
+            SyncPhase::OwnedGames => "owned_games",
+            SyncPhase::Wishlist => "wishlist",
+            SyncPhase::GameDetails => "game_details",
+            SyncPhase::Prices => "prices",
+            SyncPhase::PlayedGames => "played_games",
+            SyncPhase::Notion => "notion",
+        }
+    }
+}
+
+impl TryFrom<&str> for SyncPhase {
+    type Error = String;
+
+    /// Accepts the names phases are stored under, with hyphens or underscores, and a few
+    /// friendlier aliases such as "app-list" or "playtime"
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let name = s.replace('-', "_");
+        let name = match name.as_str() {
+            "app_list" => "steam_games",
+            "owned" => "owned_games",
+            "details" => "game_details",
+            "playtime" => "played_games",
+            other => other,
+        };
+
+        SyncPhase::ALL
+            .into_iter()
+            .find(|p| p.as_str() == name)
+            .ok_or(format!("Unknown sync phase: {}", s))
+    }
+}
+
+impl fmt::Display for SyncPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Outcome of a sync run or one of its phases
+#[derive(Clone, Debug, PartialEq)]
+pub enum SyncStatus {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+impl From<String> for SyncStatus {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "succeeded" => SyncStatus::Succeeded,
+            "failed" => SyncStatus::Failed,
+            _ => SyncStatus::Running,
+        }
+    }
+}
+
+impl From<SyncStatus> for String {
+    fn from(status: SyncStatus) -> Self {
+        match status {
+            SyncStatus::Running => "running".to_string(),
+            SyncStatus::Succeeded => "succeeded".to_string(),
+            SyncStatus::Failed => "failed".to_string(),
+        }
+    }
+}
+
+/// Represents a record in the sync_run_phase table
+#[derive(Clone, Debug, PartialEq)]
+pub struct SyncPhaseRun {
+    pub phase: SyncPhase,
+    pub started: DateTime<Utc>,
+    pub finished: DateTime<Utc>,
+    pub status: SyncStatus,
+    /// Number of items processed by the phase, e.g. games or notes read
+    pub item_count: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// Represents a record in the sync_run table, along with the phases run so far
+#[derive(Clone, Debug, PartialEq)]
+pub struct SyncRun {
+    pub id: i32,
+    pub started: DateTime<Utc>,
+    pub finished: Option<DateTime<Utc>>,
+    pub status: SyncStatus,
+    pub error: Option<String>,
+    pub phases: Vec<SyncPhaseRun>,
+}
@@ -0,0 +1,248 @@
+pub mod discord;
+pub mod email;
+pub mod slack;
+pub mod webhook;
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, TimeDelta, Utc};
+use reqwest::StatusCode;
+use serde::Serialize;
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::config;
+use crate::db::repo::{Repo, SteamGamesHandling, SyncEventHandling};
+use crate::db::sync::{RecordedEvent, SyncEvent};
+use crate::models::game::GameId;
+
+use discord::DiscordNotifier;
+use email::EmailNotifier;
+use slack::SlackNotifier;
+use webhook::WebhookNotifier;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Error, Debug)]
+pub enum NotifyError {
+    #[error("An http error occurred sending a notification: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("Notification endpoint responded with status {0}: {1}")]
+    Status(StatusCode, String),
+    #[error("Invalid email address: {0}")]
+    EmailAddress(#[from] lettre::address::AddressError),
+    #[error("Failed to build email: {0}")]
+    Email(#[from] lettre::error::Error),
+    #[error("An error occurred sending email: {0}")]
+    Smtp(#[from] lettre::transport::smtp::Error),
+    #[error("Only {} notifications were delivered: {cause}", delivered.len())]
+    Partial { delivered: Vec<i64>, cause: Box<NotifyError> },
+}
+
+impl NotifyError {
+    /// The error to give when sending stopped partway, after the given notifications were
+    /// delivered, so only the rest are retried
+    fn after_delivering(delivered: &[Notification], cause: NotifyError) -> NotifyError {
+        if delivered.is_empty() {
+            return cause;
+        }
+
+        NotifyError::Partial {
+            delivered: delivered.iter().map(|n| n.event_id).collect(),
+            cause: Box::new(cause),
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, NotifyError>;
+
+/// A recorded sync event along with what's needed to present it to a person
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Notification {
+    pub event_id: i64,
+    pub game_name: String,
+    pub recorded: DateTime<Utc>,
+    #[serde(flatten)]
+    pub event: SyncEvent,
+}
+
+impl Notification {
+    pub fn new(recorded: &RecordedEvent, game_name: &str) -> Notification {
+        Notification {
+            event_id: recorded.id,
+            game_name: game_name.to_string(),
+            recorded: recorded.recorded,
+            event: recorded.event.clone(),
+        }
+    }
+
+    pub fn game(&self) -> &GameId {
+        self.event.game()
+    }
+}
+
+/// Which kinds of event a notifier should be sent, by `SyncEvent::kind`. Everything if unset.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EventFilter(Option<HashSet<String>>);
+
+impl EventFilter {
+    pub fn new(kinds: &Option<Vec<String>>) -> EventFilter {
+        EventFilter(kinds.as_ref().map(|k| k.iter().cloned().collect()))
+    }
+
+    pub fn accepts(&self, event: &SyncEvent) -> bool {
+        self.0.as_ref().map(|kinds| kinds.contains(event.kind())).unwrap_or(true)
+    }
+}
+
+/// Build an http client for sending notifications
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build().unwrap()
+}
+
+/// POST a JSON body to the given URL, failing on any unsuccessful status
+async fn post_json(http: &reqwest::Client, url: &str, body: &Value) -> Result<()> {
+    let res = http.post(url).json(body).send().await?;
+    if !res.status().is_success() {
+        return Err(NotifyError::Status(res.status(), url.to_string()));
+    }
+    Ok(())
+}
+
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    /// Unique name for this notifier, used to keep track of the events it's delivered
+    fn name(&self) -> &str;
+
+    /// Whether this notifier should be sent the given event
+    fn accepts(&self, _event: &SyncEvent) -> bool {
+        true
+    }
+
+    /// Minimum time between deliveries, for notifiers which send a periodic summary. Events are
+    /// held back until it's been this long since the last delivery.
+    fn min_interval(&self) -> Option<TimeDelta> {
+        None
+    }
+
+    /// Deliver a batch of notifications. Notifiers which send them in several messages fail
+    /// with `NotifyError::Partial` if only some of them could be delivered.
+    async fn notify(&self, notifications: &[Notification]) -> Result<()>;
+}
+
+/// Build the notifiers configured in the [notify] section of the config
+pub fn from_config(conf: &config::Notify) -> Vec<Box<dyn Notifier>> {
+    let mut notifiers: Vec<Box<dyn Notifier>> = vec![];
+
+    for w in &conf.webhook {
+        notifiers.push(Box::new(WebhookNotifier::from_config(w)));
+    }
+    for d in &conf.discord {
+        notifiers.push(Box::new(DiscordNotifier::from_config(d)));
+    }
+    for s in &conf.slack {
+        notifiers.push(Box::new(SlackNotifier::from_config(s)));
+    }
+    for e in &conf.email {
+        notifiers.push(Box::new(EmailNotifier::from_config(e)));
+    }
+
+    notifiers
+}
+
+/// Send each notifier any recorded events it hasn't delivered yet, from the last `lookback`.
+/// Failures are reported but otherwise ignored, so the events will be retried next time.
+pub async fn dispatch(repo: &Repo, notifiers: &[Box<dyn Notifier>], lookback: TimeDelta) {
+    let now = Utc::now();
+
+    for notifier in notifiers {
+        if let Some(interval) = notifier.min_interval() {
+            match repo.get_last_delivery(notifier.name()).await {
+                Ok(Some(last)) if now - last < interval => continue,
+                Ok(_) => (),
+                Err(e) => {
+                    eprintln!("Failed to read last delivery for notifier {}: {}", notifier.name(), e);
+                    continue;
+                },
+            }
+        }
+
+        // Make sure periodic summaries cover everything since the last one
+        let since = now - lookback.max(notifier.min_interval().unwrap_or_default());
+
+        let events = match repo.get_undelivered_events(notifier.name(), &since).await {
+            Ok(e) => e,
+            Err(e) => {
+                eprintln!("Failed to read events for notifier {}: {}", notifier.name(), e);
+                continue;
+            },
+        };
+
+        if events.is_empty() {
+            continue;
+        }
+
+        // Events the notifier isn't interested in are marked skipped, so they're not considered
+        // again, but don't hold back the next periodic summary as if it had been sent
+        let (events, skipped): (Vec<RecordedEvent>, Vec<RecordedEvent>) = {
+            events.into_iter().partition(|e| notifier.accepts(&e.event))
+        };
+        let skipped_ids: Vec<i64> = skipped.iter().map(|e| e.id).collect();
+        if let Err(e) = repo.mark_events_skipped(notifier.name(), &skipped_ids).await {
+            eprintln!("Failed to mark events skipped for {}: {}", notifier.name(), e);
+        }
+
+        if events.is_empty() {
+            continue;
+        }
+        let event_ids: Vec<i64> = events.iter().map(|e| e.id).collect();
+
+        let ids: Vec<GameId> = events.iter().map(|e| *e.event.game()).collect();
+        let names_by_id = match repo.get_game_names_by_id(&ids).await {
+            Ok(n) => n,
+            Err(e) => {
+                eprintln!("Failed to get game names for notifier {}: {}", notifier.name(), e);
+                continue;
+            },
+        };
+
+        let notifications: Vec<Notification> = {
+            events
+                .iter()
+                .map(|e| {
+                    let game = e.event.game();
+                    let name = names_by_id.get(game).cloned().unwrap_or(game.to_string());
+                    Notification::new(e, &name)
+                })
+                .collect()
+        };
+
+        let mut undelivered: HashSet<i64> = HashSet::new();
+
+        println!("Sending {} notifications via {}", notifications.len(), notifier.name());
+        match notifier.notify(&notifications).await {
+            Ok(()) => (),
+            // Keep what did get through from being sent again along with the rest
+            Err(NotifyError::Partial { delivered, cause }) => {
+                eprintln!(
+                    "Failed to send {} of {} notifications via {}: {}",
+                    notifications.len() - delivered.len(), notifications.len(), notifier.name(), cause
+                );
+                undelivered = {
+                    notifications.iter().map(|n| n.event_id).filter(|id| !delivered.contains(id)).collect()
+                };
+            },
+            Err(e) => {
+                eprintln!("Failed to send notifications via {}: {}", notifier.name(), e);
+                continue;
+            },
+        }
+
+        let event_ids: Vec<i64> = event_ids.into_iter().filter(|id| !undelivered.contains(id)).collect();
+        if let Err(e) = repo.mark_events_delivered(notifier.name(), &event_ids).await {
+            eprintln!("Failed to mark events delivered for {}: {}", notifier.name(), e);
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use crate::config;
+use crate::db::sync::SyncEvent;
+use crate::models::game::ReleaseChange;
+use crate::notify::{http_client, post_json, EventFilter, Notification, Notifier, NotifyError, Result};
+
+// Discord rejects messages with more embeds than this
+const MAX_EMBEDS_PER_MESSAGE: usize = 10;
+
+const COLOUR_RELEASED: u32 = 0x2ecc71;
+const COLOUR_DELAYED: u32 = 0xe74c3c;
+const COLOUR_BROUGHT_FORWARD: u32 = 0x2ecc71;
+const COLOUR_NARROWED: u32 = 0x3498db;
+const COLOUR_WIDENED: u32 = 0xe67e22;
+const COLOUR_TBA: u32 = 0x8e44ad;
+const COLOUR_OTHER: u32 = 0x95a5a6;
+
+/// Posts notifications to a discord channel as rich embeds, via an incoming webhook
+pub struct DiscordNotifier {
+    name: String,
+    webhook_url: String,
+    filter: EventFilter,
+    http: reqwest::Client,
+}
+
+impl DiscordNotifier {
+    pub fn new(name: &str, webhook_url: &str) -> DiscordNotifier {
+        DiscordNotifier {
+            name: name.to_string(),
+            webhook_url: webhook_url.to_string(),
+            filter: EventFilter::default(),
+            http: http_client(),
+        }
+    }
+
+    /// Only send events of the given kinds
+    pub fn with_filter(self, filter: EventFilter) -> DiscordNotifier {
+        DiscordNotifier { filter, ..self }
+    }
+
+    pub fn from_config(conf: &config::ChatWebhook) -> DiscordNotifier {
+        DiscordNotifier::new(&conf.name, &conf.webhook_url).with_filter(EventFilter::new(&conf.events))
+    }
+
+    /// Render a notification as a discord embed
+    pub fn embed(n: &Notification) -> Value {
+        let game = n.game();
+
+        match &n.event {
+            SyncEvent::Released { .. } => json!({
+                "title": format!("🚀 {} is out now", &n.game_name),
+                "url": game.store_url(),
+                "description": "Newly released on Steam",
+                "color": COLOUR_RELEASED,
+                "image": { "url": game.header_image_url() },
+                "timestamp": n.recorded.to_rfc3339(),
+            }),
+            SyncEvent::ReleaseDateUpdated { prev_text, new_text, change, .. } => {
+                let colour = match change {
+                    ReleaseChange::Postponed => COLOUR_DELAYED,
+                    ReleaseChange::BroughtForward => COLOUR_BROUGHT_FORWARD,
+                    ReleaseChange::Narrowed => COLOUR_NARROWED,
+                    ReleaseChange::Widened => COLOUR_WIDENED,
+                    ReleaseChange::Tba => COLOUR_TBA,
+                    ReleaseChange::Changed => COLOUR_OTHER,
+                };
+
+                json!({
+                    "title": format!("{}: {}", change.label(), &n.game_name),
+                    "url": game.store_url(),
+                    "color": colour,
+                    "thumbnail": { "url": game.header_image_url() },
+                    "fields": [
+                        { "name": "Was", "value": prev_text, "inline": true },
+                        { "name": "Now", "value": new_text, "inline": true },
+                    ],
+                    "timestamp": n.recorded.to_rfc3339(),
+                })
+            },
+            e => json!({
+                "title": &n.game_name,
+                "url": game.store_url(),
+                "description": e.describe(&n.game_name),
+                "color": COLOUR_OTHER,
+                "thumbnail": { "url": game.header_image_url() },
+                "timestamp": n.recorded.to_rfc3339(),
+            }),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for DiscordNotifier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn accepts(&self, event: &SyncEvent) -> bool {
+        self.filter.accepts(event)
+    }
+
+    async fn notify(&self, notifications: &[Notification]) -> Result<()> {
+        for (i, chunk) in notifications.chunks(MAX_EMBEDS_PER_MESSAGE).enumerate() {
+            let embeds: Vec<Value> = chunk.iter().map(Self::embed).collect();
+            let body = json!({ "username": "rusteam", "embeds": embeds });
+
+            if let Err(e) = post_json(&self.http, &self.webhook_url, &body).await {
+                let sent = &notifications[..i * MAX_EMBEDS_PER_MESSAGE];
+                return Err(NotifyError::after_delivering(sent, e));
+            }
+        }
+        Ok(())
+    }
+}
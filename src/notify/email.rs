@@ -0,0 +1,204 @@
+use async_trait::async_trait;
+use chrono::TimeDelta;
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use crate::config::{self, SmtpSecurity};
+use crate::db::sync::SyncEvent;
+use crate::models::game::ReleaseChange;
+use crate::notify::{EventFilter, Notification, Notifier, Result};
+
+/// Sections of the digest, in the order they're shown
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DigestSection {
+    Released,
+    Delayed,
+    Narrowed,
+    OnSale,
+    OtherReleaseChanges,
+}
+
+impl DigestSection {
+    const ALL: [DigestSection; 5] = [
+        DigestSection::Released,
+        DigestSection::Delayed,
+        DigestSection::Narrowed,
+        DigestSection::OnSale,
+        DigestSection::OtherReleaseChanges,
+    ];
+
+    pub fn for_event(event: &SyncEvent) -> DigestSection {
+        match event {
+            SyncEvent::Released { .. } => DigestSection::Released,
+            SyncEvent::ReleaseDateUpdated { change, .. } => {
+                match change {
+                    ReleaseChange::Postponed | ReleaseChange::Widened | ReleaseChange::Tba => DigestSection::Delayed,
+                    ReleaseChange::Narrowed | ReleaseChange::BroughtForward => DigestSection::Narrowed,
+                    ReleaseChange::Changed => DigestSection::OtherReleaseChanges,
+                }
+            },
+            SyncEvent::OnSale { .. } |
+                SyncEvent::HistoricalLow { .. } |
+                SyncEvent::BelowTargetPrice { .. } => DigestSection::OnSale,
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            DigestSection::Released => "Released",
+            DigestSection::Delayed => "Delayed",
+            DigestSection::Narrowed => "Narrowed down or brought forward",
+            DigestSection::OnSale => "On sale",
+            DigestSection::OtherReleaseChanges => "Other release date changes",
+        }
+    }
+}
+
+/// Notifications grouped into sections for a summary email
+#[derive(Debug)]
+pub struct Digest<'a> {
+    sections: Vec<(DigestSection, Vec<&'a Notification>)>,
+}
+
+/// Escape text for inclusion in HTML
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+impl<'a> Digest<'a> {
+    pub fn new(notifications: &'a [Notification]) -> Digest<'a> {
+        let sections = {
+            DigestSection::ALL
+                .into_iter()
+                .map(|section| {
+                    let items: Vec<&Notification> = {
+                        notifications
+                            .iter()
+                            .filter(|n| DigestSection::for_event(&n.event) == section)
+                            .collect()
+                    };
+                    (section, items)
+                })
+                .filter(|(_, items)| !items.is_empty())
+                .collect()
+        };
+
+        Digest { sections }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+
+    pub fn text(&self) -> String {
+        let mut out = String::new();
+
+        for (section, items) in &self.sections {
+            out.push_str(&format!("{} ({})\n", section.title(), items.len()));
+            for n in items {
+                out.push_str(&format!("  - {}\n", n.event.describe(&n.game_name)));
+                out.push_str(&format!("    {}\n", n.game().store_url()));
+            }
+            out.push('\n');
+        }
+
+        out
+    }
+
+    pub fn html(&self) -> String {
+        let mut out = String::from("<html><body>\n");
+
+        for (section, items) in &self.sections {
+            out.push_str(&format!("<h2>{} ({})</h2>\n<ul>\n", section.title(), items.len()));
+            for n in items {
+                let name = escape_html(&n.game_name);
+                let link = format!("<a href=\"{}\">{}</a>", n.game().store_url(), &name);
+
+                // Describe with a placeholder name so the rest of the text can be escaped
+                let description = escape_html(&n.event.describe("\u{0}")).replace('\u{0}', &link);
+                out.push_str(&format!("<li>{}</li>\n", description));
+            }
+            out.push_str("</ul>\n");
+        }
+
+        out.push_str("</body></html>\n");
+        out
+    }
+}
+
+/// Emails a digest of notifications over SMTP
+pub struct EmailNotifier {
+    name: String,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: String,
+    to: Vec<String>,
+    subject: String,
+    min_interval: Option<TimeDelta>,
+    filter: EventFilter,
+}
+
+impl EmailNotifier {
+    pub fn from_config(conf: &config::Email) -> EmailNotifier {
+        let builder = match conf.security {
+            SmtpSecurity::StartTls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&conf.smtp_host)
+                    .expect("Failed to set up SMTP connection")
+            },
+            SmtpSecurity::Tls => {
+                AsyncSmtpTransport::<Tokio1Executor>::relay(&conf.smtp_host)
+                    .expect("Failed to set up SMTP connection")
+            },
+            SmtpSecurity::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&conf.smtp_host),
+        };
+        let builder = builder.port(conf.smtp_port);
+        let builder = match (&conf.username, &conf.password) {
+            (Some(u), Some(p)) => builder.credentials(Credentials::new(u.to_string(), p.to_string())),
+            _ => builder,
+        };
+
+        EmailNotifier {
+            name: conf.name.to_string(),
+            transport: builder.build(),
+            from: conf.from.to_string(),
+            to: conf.to.clone(),
+            subject: conf.subject.to_string(),
+            min_interval: conf.min_interval_hours.map(TimeDelta::hours),
+            filter: EventFilter::new(&conf.events),
+        }
+    }
+
+    fn message(&self, digest: &Digest) -> Result<Message> {
+        let mut builder = Message::builder().from(self.from.parse::<Mailbox>()?).subject(&self.subject);
+        for to in &self.to {
+            builder = builder.to(to.parse::<Mailbox>()?);
+        }
+
+        Ok(builder.multipart(MultiPart::alternative_plain_html(digest.text(), digest.html()))?)
+    }
+}
+
+#[async_trait]
+impl Notifier for EmailNotifier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn accepts(&self, event: &SyncEvent) -> bool {
+        self.filter.accepts(event)
+    }
+
+    fn min_interval(&self) -> Option<TimeDelta> {
+        self.min_interval
+    }
+
+    async fn notify(&self, notifications: &[Notification]) -> Result<()> {
+        let digest = Digest::new(notifications);
+        if digest.is_empty() {
+            return Ok(());
+        }
+
+        self.transport.send(self.message(&digest)?).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use crate::config;
+use crate::db::sync::SyncEvent;
+use crate::notify::{http_client, post_json, EventFilter, Notification, Notifier, NotifyError, Result};
+
+// Slack allows up to 50 blocks per message, and each notification takes two
+const MAX_NOTIFICATIONS_PER_MESSAGE: usize = 20;
+
+/// Posts notifications to a slack channel as blocks, via an incoming webhook
+pub struct SlackNotifier {
+    name: String,
+    webhook_url: String,
+    filter: EventFilter,
+    http: reqwest::Client,
+}
+
+impl SlackNotifier {
+    pub fn new(name: &str, webhook_url: &str) -> SlackNotifier {
+        SlackNotifier {
+            name: name.to_string(),
+            webhook_url: webhook_url.to_string(),
+            filter: EventFilter::default(),
+            http: http_client(),
+        }
+    }
+
+    /// Only send events of the given kinds
+    pub fn with_filter(self, filter: EventFilter) -> SlackNotifier {
+        SlackNotifier { filter, ..self }
+    }
+
+    pub fn from_config(conf: &config::ChatWebhook) -> SlackNotifier {
+        SlackNotifier::new(&conf.name, &conf.webhook_url).with_filter(EventFilter::new(&conf.events))
+    }
+
+    /// Render a notification as a section block followed by a divider
+    pub fn blocks(n: &Notification) -> Vec<Value> {
+        let game = n.game();
+        let link = format!("*<{}|{}>*", game.store_url(), &n.game_name);
+
+        let text = match &n.event {
+            SyncEvent::Released { .. } => format!("🚀 {} is out now!", link),
+            SyncEvent::ReleaseDateUpdated { prev_text, new_text, change, .. } => {
+                format!("{}: {}\n~{}~ → *{}*", change.label(), link, prev_text, new_text)
+            },
+            e => e.describe(&link),
+        };
+
+        vec![
+            json!({
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": text },
+                "accessory": {
+                    "type": "image",
+                    "image_url": game.header_image_url(),
+                    "alt_text": &n.game_name,
+                },
+            }),
+            json!({ "type": "divider" }),
+        ]
+    }
+}
+
+#[async_trait]
+impl Notifier for SlackNotifier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn accepts(&self, event: &SyncEvent) -> bool {
+        self.filter.accepts(event)
+    }
+
+    async fn notify(&self, notifications: &[Notification]) -> Result<()> {
+        for (i, chunk) in notifications.chunks(MAX_NOTIFICATIONS_PER_MESSAGE).enumerate() {
+            let blocks: Vec<Value> = chunk.iter().flat_map(Self::blocks).collect();
+
+            // Plain text is shown where blocks can't be, e.g. in push notifications
+            let fallback = {
+                chunk
+                    .iter()
+                    .map(|n| n.event.describe(&n.game_name))
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            };
+            let body = json!({ "text": fallback, "blocks": blocks });
+
+            if let Err(e) = post_json(&self.http, &self.webhook_url, &body).await {
+                let sent = &notifications[..i * MAX_NOTIFICATIONS_PER_MESSAGE];
+                return Err(NotifyError::after_delivering(sent, e));
+            }
+        }
+        Ok(())
+    }
+}
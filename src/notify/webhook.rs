@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use serde::Serialize;
+
+use crate::config;
+use crate::db::sync::SyncEvent;
+use crate::notify::{http_client, EventFilter, Notification, Notifier, NotifyError, Result};
+
+/// Body POSTed to a webhook for each batch of notifications
+#[derive(Debug, Serialize)]
+struct WebhookPayload<'a> {
+    source: &'static str,
+    events: &'a [Notification],
+}
+
+/// Sends notifications as generic JSON to any HTTP endpoint
+pub struct WebhookNotifier {
+    name: String,
+    url: String,
+    headers: HashMap<String, String>,
+    filter: EventFilter,
+    http: reqwest::Client,
+}
+
+impl WebhookNotifier {
+    pub fn new(name: &str, url: &str) -> WebhookNotifier {
+        WebhookNotifier {
+            name: name.to_string(),
+            url: url.to_string(),
+            headers: HashMap::new(),
+            filter: EventFilter::default(),
+            http: http_client(),
+        }
+    }
+
+    /// Add headers to send with every request, e.g. for authentication
+    pub fn with_headers(self, headers: HashMap<String, String>) -> WebhookNotifier {
+        WebhookNotifier { headers, ..self }
+    }
+
+    /// Only send events of the given kinds
+    pub fn with_filter(self, filter: EventFilter) -> WebhookNotifier {
+        WebhookNotifier { filter, ..self }
+    }
+
+    pub fn from_config(conf: &config::Webhook) -> WebhookNotifier {
+        WebhookNotifier::new(&conf.name, &conf.url)
+            .with_headers(conf.headers.clone())
+            .with_filter(EventFilter::new(&conf.events))
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn accepts(&self, event: &SyncEvent) -> bool {
+        self.filter.accepts(event)
+    }
+
+    async fn notify(&self, notifications: &[Notification]) -> Result<()> {
+        let payload = WebhookPayload { source: "rusteam", events: notifications };
+
+        let mut req = self.http.post(&self.url).json(&payload);
+        for (k, v) in &self.headers {
+            req = req.header(k, v);
+        }
+
+        let res = req.send().await?;
+        if !res.status().is_success() {
+            return Err(NotifyError::Status(res.status(), self.url.clone()));
+        }
+        Ok(())
+    }
+}
@@ -5,9 +5,9 @@ use std::str::FromStr;
 
 use async_trait::async_trait;
 
-use ::notion::NotionApi;
 use ::notion::ids::DatabaseId;
-use ::notion::models::Properties;
+use ::notion::models::{ListResponse, Page, Properties};
+use ::notion::models::paging::{Pageable, PagingCursor};
 use ::notion::models::properties::PropertyValue;
 use ::notion::models::search::DatabaseQuery;
 use thiserror::Error;
@@ -22,6 +22,8 @@ pub enum NotionError {
     Api(#[from] notion::Error),
     #[error("An http error occurred fetching data from Notion via ureq: {0}")]
     Http(#[from] ureq::Error),
+    #[error("An IO error occurred reading a response from Notion: {0}")]
+    Io(#[from] std::io::Error),
     #[error("Format error: {0}")]
     Fmt(#[from] std::fmt::Error),
 }
@@ -35,7 +37,6 @@ pub trait NotionHandling {
 }
 
 pub struct NotionGamesRepo {
-    api: NotionApi,
     database_id: String,
     api_key: String,
     api_host: String,
@@ -44,7 +45,6 @@ pub struct NotionGamesRepo {
 impl NotionGamesRepo {
     pub fn new(api_key: &str, database_id: &str, api_host: &str) -> NotionGamesRepo {
         NotionGamesRepo {
-            api: NotionApi::new(api_key.to_string()).unwrap(),
             database_id: database_id.to_string(),
             api_key: api_key.to_string(),
             api_host: api_host.to_string(),
@@ -64,6 +64,22 @@ impl NotionGamesRepo {
 
         Ok(())
     }
+
+    // Fetch a single page of results from the notes database, starting from the given cursor.
+    // As with update_row, we query the API directly with ureq rather than via the notion crate,
+    // as the crate doesn't allow overriding the API host.
+    fn query_page(&self, db_id: &DatabaseId, cursor: Option<PagingCursor>) -> Result<ListResponse<Page>> {
+        let query = DatabaseQuery { sorts: None, filter: None, paging: None }.start_from(cursor);
+
+        let url = format!("{}/v1/databases/{}/query", &self.api_host, db_id);
+        let res = ureq::post(&url)
+            .set("Authorization", &format!("Bearer {}", self.api_key))
+            .set("Content-Type", "application/json")
+            .set("Notion-Version", "2022-06-28")
+            .send_json(&query)?;
+
+        Ok(res.into_json::<ListResponse<Page>>()?)
+    }
 }
 
 #[async_trait]
@@ -71,11 +87,23 @@ impl NotionHandling for NotionGamesRepo {
     async fn get_notes(&self) -> Result<Vec<GameNote>> {
         let db_id = DatabaseId::from_str(&self.database_id)?;
 
+        // Notion returns at most 100 rows per query, so follow the cursor until we've read
+        // the entire database
+        let mut pages: Vec<Page> = vec![];
+        let mut cursor: Option<PagingCursor> = None;
+
+        loop {
+            let res = self.query_page(&db_id, cursor)?;
+            pages.extend(res.results);
+
+            match res.next_cursor {
+                Some(next) if res.has_more => cursor = Some(next),
+                _ => break,
+            }
+        }
+
         Ok(
-            self.api
-                .query_database(&db_id, DatabaseQuery {sorts: None, filter: None, paging: None})
-                .await?
-                .results
+            pages
                 .into_iter()
                 .filter_map(|page| match GameNote::try_from(page) {
                     Ok(note) => Some(note),
@@ -110,3 +138,32 @@ impl NotionHandling for NotionGamesRepo {
         Ok(self.update_row(note_id, props)?)
     }
 }
+
+/// Reads notes as usual, but only prints the changes it would make to them
+pub struct DryRunNotion<N: NotionHandling> {
+    inner: N,
+}
+
+impl<N: NotionHandling> DryRunNotion<N> {
+    pub fn new(inner: N) -> DryRunNotion<N> {
+        DryRunNotion { inner }
+    }
+}
+
+#[async_trait]
+impl<N: NotionHandling + Send + Sync> NotionHandling for DryRunNotion<N> {
+    async fn get_notes(&self) -> Result<Vec<GameNote>> {
+        self.inner.get_notes().await
+    }
+
+    fn set_game_details(&self, note_id: &str, app_id: &str, name: &str) -> Result<()> {
+        println!("[dry run] Would set details in notion for note {}: {} ({})", note_id, name, app_id);
+        Ok(())
+    }
+
+    fn set_state(&self, note_id: &str, state: &GameState) -> Result<()> {
+        let pretty_state: String = state.to_owned().into();
+        println!("[dry run] Would set state in notion: note {} = {}", note_id, &pretty_state);
+        Ok(())
+    }
+}
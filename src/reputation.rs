@@ -0,0 +1,100 @@
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::models::game::{GameId, StudioReleaseUpdate};
+
+/// How reliably a developer or publisher has kept to the release dates it announced
+#[derive(Clone, Debug, PartialEq)]
+pub struct StudioReputation {
+    pub studio: String,
+    pub role: String,
+    /// Games tracked with details, whether or not their release date ever changed
+    pub games: usize,
+    /// Games delayed at least once
+    pub games_delayed: usize,
+    /// Every delay across all their games
+    pub delays: usize,
+    /// How far release estimates were pushed back in total, where that can be measured
+    pub total_slip: TimeDelta,
+}
+
+impl StudioReputation {
+    /// Share of their games which have been delayed, from 0 to 1
+    pub fn delay_rate(&self) -> f64 {
+        if self.games == 0 { 0.0 } else { self.games_delayed as f64 / self.games as f64 }
+    }
+
+    /// How far the release is pushed back each time a game of theirs is delayed
+    pub fn average_slip(&self) -> TimeDelta {
+        if self.delays == 0 { TimeDelta::zero() } else { self.total_slip / self.delays as i32 }
+    }
+
+    /// How far any one of their games can be expected to slip, judging by their history
+    pub fn expected_slip(&self) -> TimeDelta {
+        if self.games == 0 { TimeDelta::zero() } else { self.total_slip / self.games as i32 }
+    }
+}
+
+/// Score each studio on its history of release date changes, worst delayers first
+///
+/// Games whose release date never changed are expected among the updates too, with no change,
+/// so they count towards how often the studio is on time.
+pub fn rank_studios(updates: &[StudioReleaseUpdate]) -> Vec<StudioReputation> {
+    let mut games: HashMap<(&str, &str), HashSet<GameId>> = HashMap::new();
+    let mut delayed: HashMap<(&str, &str), HashSet<GameId>> = HashMap::new();
+    let mut delays: HashMap<(&str, &str), (usize, TimeDelta)> = HashMap::new();
+
+    for u in updates {
+        let key = (u.studio.as_str(), u.role.as_str());
+        games.entry(key).or_default().insert(u.game);
+
+        if u.change.is_some_and(|c| c.is_delay()) {
+            delayed.entry(key).or_default().insert(u.game);
+
+            // Going back to TBA counts as a delay, but there's no way to say by how much
+            let slip = {
+                u.prev_estimate
+                    .zip(u.new_estimate)
+                    .map(|(prev, new)| (new - prev).max(TimeDelta::zero()))
+                    .unwrap_or_default()
+            };
+            let entry = delays.entry(key).or_insert((0, TimeDelta::zero()));
+            entry.0 += 1;
+            entry.1 += slip;
+        }
+    }
+
+    let mut reputations: Vec<StudioReputation> = {
+        games
+            .into_iter()
+            .map(|(key @ (studio, role), g)| {
+                let (delay_count, total_slip) = delays.get(&key).cloned().unwrap_or_default();
+
+                StudioReputation {
+                    studio: studio.to_string(),
+                    role: role.to_string(),
+                    games: g.len(),
+                    games_delayed: delayed.get(&key).map(|d| d.len()).unwrap_or(0),
+                    delays: delay_count,
+                    total_slip,
+                }
+            })
+            .collect()
+    };
+
+    reputations.sort_by_key(|r| {
+        (Reverse(r.expected_slip()), Reverse(r.delays), Reverse(r.games), r.studio.clone(), r.role.clone())
+    });
+    reputations
+}
+
+/// Push a release estimate back by the worst expected slip of the studios behind the game
+pub fn adjusted_estimate(
+    estimate: Option<DateTime<Utc>>,
+    studios: &[&StudioReputation]
+) -> Option<DateTime<Utc>> {
+    let slip = studios.iter().map(|s| s.expected_slip()).max().unwrap_or_default();
+    estimate.map(|e| e + slip)
+}
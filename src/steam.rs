@@ -1,19 +1,33 @@
 pub mod conv;
+pub mod ratelimit;
+pub mod release_date;
 
 use std::collections::HashMap;
 use std::time::Duration;
 
+use async_trait::async_trait;
 use chrono::{DateTime, TimeZone, Utc};
+use futures::stream::{self, StreamExt};
+use itertools::Itertools;
+use reqwest::StatusCode;
 use thiserror::Error;
 use ureq;
 
-use crate::models::game::{GameDetails, GameId, SteamPlaytime, WishlistedGame};
+use ratelimit::RateLimiter;
+
+use crate::models::game::{
+    AppType, GameDetails, GameId, GamePrice, SteamApp, SteamPlaytime, WishlistedGame
+};
 use crate::models::steam::*;
 
 #[derive(Error, Debug)]
 pub enum SteamError {
     #[error("An http error occurred fetching data from steam: {0}")]
     Http(#[from] ureq::Error),
+    #[error("An http error occurred fetching data from the steam store: {0}")]
+    Store(#[from] reqwest::Error),
+    #[error("The steam store responded with status {0} for {1}")]
+    StoreStatus(StatusCode, String),
     #[error("An IO error occurred fetching data from steam: {0}")]
     Io(#[from] std::io::Error),
     #[error("A JSON decoding error occurred parsing data from steam: {0}")]
@@ -22,19 +36,79 @@ pub enum SteamError {
     Conv(String),
 }
 
+impl SteamError {
+    /// Whether the error is likely to go away if we try again later, e.g. throttling, server
+    /// errors or network problems, as opposed to steam having nothing usable for the request
+    pub fn is_transient(&self) -> bool {
+        match self {
+            SteamError::Store(e) => e.is_timeout() || e.is_connect() || e.is_request() || e.is_body(),
+            SteamError::StoreStatus(status, _) => {
+                // The store also responds with 403 Forbidden when it's throttling us
+                *status == StatusCode::TOO_MANY_REQUESTS ||
+                    *status == StatusCode::FORBIDDEN ||
+                    status.is_server_error()
+            },
+            SteamError::Io(_) => true,
+            _ => false,
+        }
+    }
+}
+
 pub type Result<T> = std::result::Result<T, SteamError>;
 
+/// Why details couldn't be retrieved for a game
+#[derive(Clone, Debug, PartialEq)]
+pub enum DetailsFailure {
+    /// A problem which may go away by itself, so the game should just be tried again next time
+    Transient(String),
+    /// Steam doesn't have a usable definition for the game, e.g. a malformed response or a 404
+    Permanent(String),
+}
+
+/// Games whose details or prices couldn't be retrieved, along with the reason why
+pub type DetailsFailures = Vec<(GameId, DetailsFailure)>;
+
+impl From<&SteamError> for DetailsFailure {
+    fn from(e: &SteamError) -> Self {
+        if e.is_transient() {
+            DetailsFailure::Transient(e.to_string())
+        } else {
+            DetailsFailure::Permanent(e.to_string())
+        }
+    }
+}
+
+// Number of apps to request per page from IStoreService/GetAppList, which allows up to 50k
+const APP_LIST_PAGE_SIZE: u32 = 50000;
+
+// Number of app IDs to request at once from appdetails when only fetching price_overview
+const PRICE_BATCH_SIZE: usize = 100;
+
 pub trait SteamPlayerServiceHandling {
     fn get_owned_games(&self, account_id: &str) -> Result<Vec<GameId>>;
     fn get_played_games(&self, account_id: &str) -> Result<Vec<SteamPlaytime>>;
 }
 
 pub trait SteamAppsServiceHandling {
-    fn get_all_games(&self) -> Result<Vec<SteamAppIdPair>>;
+    /// Apps of the given type added or changed since the given time, or all of them if unset
+    fn get_app_list(
+        &self,
+        app_type: AppType,
+        since: Option<DateTime<Utc>>
+    ) -> Result<Vec<SteamApp>>;
 }
 
+#[async_trait]
 pub trait SteamAppDetailsHandling {
-    fn get_game_details(&self, ids: &[GameId]) -> Result<(Vec<GameDetails>, Vec<GameId>)>;
+    async fn get_game_details(
+        &self,
+        ids: &[GameId]
+    ) -> Result<(Vec<GameDetails>, DetailsFailures)>;
+}
+
+#[async_trait]
+pub trait SteamPriceHandling {
+    async fn get_prices(&self, ids: &[GameId]) -> Result<(Vec<GamePrice>, DetailsFailures)>;
 }
 
 pub trait SteamWishlistHandling {
@@ -46,20 +120,60 @@ pub trait SteamHandling:
     SteamPlayerServiceHandling +
     SteamAppsServiceHandling +
     SteamAppDetailsHandling +
+    SteamPriceHandling +
     SteamWishlistHandling {}
 
+/// Limits on how hard we hit the store API, which throttles at around 200 requests per 5 minutes
+#[derive(Clone, Debug)]
+pub struct StoreLimits {
+    pub requests_per_window: u32,
+    pub window: Duration,
+    pub concurrency: usize,
+    pub max_retries: u32,
+    pub backoff: Duration,
+}
+
+impl Default for StoreLimits {
+    fn default() -> Self {
+        StoreLimits {
+            requests_per_window: 200,
+            window: Duration::from_secs(300),
+            concurrency: 4,
+            max_retries: 3,
+            backoff: Duration::from_secs(5),
+        }
+    }
+}
+
 pub struct SteamClient {
     api_key: String,
     api_host: String,  // https://api.steampowered.com
     store_host: String, // https://store.steampowered.com
+    store_http: reqwest::Client,
+    store_limits: StoreLimits,
+    store_limiter: RateLimiter,
 }
 
 impl SteamClient {
     pub fn new(api_key: &str, api_host: &str, store_host: &str) -> SteamClient {
+        let store_limits = StoreLimits::default();
+
         SteamClient {
             api_key: api_key.to_string(),
             api_host: api_host.to_string(),
             store_host: store_host.to_string(),
+            store_http: reqwest::Client::new(),
+            store_limiter: RateLimiter::per_window(store_limits.requests_per_window, store_limits.window),
+            store_limits,
+        }
+    }
+
+    /// Replace the default limits on requests to the store API
+    pub fn with_store_limits(self, limits: StoreLimits) -> SteamClient {
+        SteamClient {
+            store_limiter: RateLimiter::per_window(limits.requests_per_window, limits.window),
+            store_limits: limits,
+            ..self
         }
     }
 
@@ -102,37 +216,131 @@ impl SteamPlayerServiceHandling for SteamClient {
     }
 }
 
+impl SteamClient {
+    /// Which of GetAppList's include_* flags selects the given type of app
+    fn app_list_param(app_type: AppType) -> &'static str {
+        match app_type {
+            AppType::Game => "include_games",
+            AppType::Dlc => "include_dlc",
+            AppType::Software => "include_software",
+            AppType::Video => "include_videos",
+            AppType::Hardware => "include_hardware",
+        }
+    }
+}
+
 impl SteamAppsServiceHandling for SteamClient {
-    fn get_all_games(&self) -> Result<Vec<SteamAppIdPair>> {
-        let req = ureq::get(&format!("{}/ISteamApps/GetAppList/v2/", &self.api_host));
-        let res = req.call()?.into_json::<SteamAllGamesResponse>()?;
+    fn get_app_list(
+        &self,
+        app_type: AppType,
+        since: Option<DateTime<Utc>>
+    ) -> Result<Vec<SteamApp>> {
+        let mut apps: Vec<SteamApp> = vec![];
+        let mut last_appid: Option<u32> = None;
+
+        // The response doesn't say what type each app is, so only ask for one type at a time
+        loop {
+            let mut req = ureq::get(&format!("{}/IStoreService/GetAppList/v1/", &self.api_host))
+                .query("key", &self.api_key)
+                .query("max_results", &APP_LIST_PAGE_SIZE.to_string());
+
+            for t in AppType::ALL {
+                req = req.query(Self::app_list_param(t), if t == app_type { "true" } else { "false" });
+            }
+            if let Some(s) = since {
+                req = req.query("if_modified_since", &s.timestamp().to_string());
+            }
+            if let Some(id) = last_appid {
+                req = req.query("last_appid", &id.to_string());
+            }
+
+            let page = req.call()?.into_json::<SteamStoreAppListResponse>()?.response;
+            apps.extend(page.apps.into_iter().map(|a| conv::extract_app(a, app_type)));
+
+            match page.last_appid {
+                Some(id) if page.have_more_results && last_appid != Some(id) => last_appid = Some(id),
+                _ => break,
+            }
+        }
 
-        Ok(res.applist.apps)
+        Ok(apps)
     }
 }
 
 impl SteamClient {
-    fn get_game_details_internal(&self, ids: &[GameId]) -> Result<(HashMap<GameId, SteamAppDetails>, Vec<GameId>)> {
+    /// Make a GET request to the store API, waiting on the rate limiter first. If we're throttled
+    /// or steam has a temporary problem, retry with exponential backoff, honouring Retry-After.
+    async fn store_get(&self, path: &str, query: &[(&str, &str)]) -> Result<String> {
+        let url = format!("{}{}", &self.store_host, path);
+        let mut attempt: u32 = 0;
+
+        loop {
+            self.store_limiter.acquire().await;
+
+            let res = self.store_http.get(&url).query(query).send().await;
+
+            let (err, retry_after) = match res {
+                Ok(r) if r.status().is_success() => return Ok(r.text().await?),
+                Ok(r) => {
+                    let retry_after = {
+                        r.headers()
+                            .get(reqwest::header::RETRY_AFTER)
+                            .and_then(|h| h.to_str().ok())
+                            .and_then(|h| h.parse::<u64>().ok())
+                            .map(Duration::from_secs)
+                    };
+                    (SteamError::StoreStatus(r.status(), url.clone()), retry_after)
+                },
+                Err(e) => (SteamError::from(e), None),
+            };
+
+            if !err.is_transient() || attempt >= self.store_limits.max_retries {
+                return Err(err);
+            }
+
+            let delay = retry_after.unwrap_or(self.store_limits.backoff * 2u32.pow(attempt));
+
+            eprintln!("Error from steam store: {}; retrying in {:?}", &err, delay);
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    async fn get_game_details_internal(
+        &self,
+        ids: &[GameId]
+    ) -> Result<(HashMap<GameId, SteamAppDetails>, DetailsFailures)> {
         let mut results: HashMap<GameId, SteamAppDetailsResponseEntry> = HashMap::new();
-        let mut failures: Vec<GameId> = vec![];
+        let mut failures: DetailsFailures = vec![];
 
-        // Requests have to be made one by one unless we're only getting price_overview
-        // TODO: This would be better done with an async http library rather than ureq
-        for &id in ids {
-            let appid: String = id.into();
+        // Requests have to be made one by one unless we're only getting price_overview, so make
+        // several at once, leaving the rate limiter to keep us within the store limits
+        let responses: Vec<(GameId, Result<String>)> = {
+            stream::iter(ids.iter().copied())
+                .map(|id| async move {
+                    let appid: String = id.into();
+                    let query = [("currency", "GBP"), ("appids", appid.as_str())];
 
-            let req = {
-                ureq::get(&format!("{}/api/appdetails", &self.store_host))
-                    .query("currency", "GBP")
-                    .query("appids", &appid)
-            };
+                    (id, self.store_get("/api/appdetails", &query).await)
+                })
+                .buffer_unordered(self.store_limits.concurrency.max(1))
+                .collect()
+                .await
+        };
 
+        // Problems with individual games shouldn't stop us getting details for the rest
+        for (id, body) in responses {
             let res = {
-                match req.call()?.into_json::<SteamAppDetailsResponse>() {
+                let parsed = match body {
+                    Ok(b) => serde_json::from_str::<SteamAppDetailsResponse>(&b).map_err(SteamError::from),
+                    Err(e) => Err(e),
+                };
+
+                match parsed {
                     Ok(r) => r,
                     Err(e) => {
-                        eprintln!("Bad JSON response from steam for appid {}: {}; skipping.", &id, &e);
-                        failures.push(id.clone());
+                        eprintln!("Failed to get details from steam for appid {}: {}; skipping.", &id, &e);
+                        failures.push((id, DetailsFailure::from(&e)));
                         continue;
                     }
                 }
@@ -152,11 +360,15 @@ impl SteamClient {
     }
 }
 
+#[async_trait]
 impl SteamAppDetailsHandling for SteamClient {
-    fn get_game_details(&self, ids: &[GameId]) -> Result<(Vec<GameDetails>, Vec<GameId>)> {
+    async fn get_game_details(
+        &self,
+        ids: &[GameId]
+    ) -> Result<(Vec<GameDetails>, DetailsFailures)> {
         let now = Utc::now();
 
-        let (details, failures) = self.get_game_details_internal(ids)?;
+        let (details, failures) = self.get_game_details_internal(ids).await?;
         Ok(
             (
                 details
@@ -169,6 +381,50 @@ impl SteamAppDetailsHandling for SteamClient {
     }
 }
 
+#[async_trait]
+impl SteamPriceHandling for SteamClient {
+    /// Get current prices for the given games. Unlike the full app details, appdetails lets us
+    /// request several apps at once if we filter it down to price_overview, so do it in batches.
+    /// Games without a price (free or not yet purchasable) are omitted from the results.
+    /// A batch which can't be retrieved is skipped, with each of its games reported as failed.
+    async fn get_prices(&self, ids: &[GameId]) -> Result<(Vec<GamePrice>, DetailsFailures)> {
+        let now = Utc::now();
+        let mut prices: Vec<GamePrice> = vec![];
+        let mut failures: DetailsFailures = vec![];
+
+        for batch in ids.chunks(PRICE_BATCH_SIZE) {
+            let appids: String = batch.iter().join(",");
+            let query = [("currency", "GBP"), ("filters", "price_overview"), ("appids", &appids)];
+
+            // Problems with one batch shouldn't stop us getting prices for the rest
+            let parsed = match self.store_get("/api/appdetails", &query).await {
+                Ok(b) => serde_json::from_str::<SteamPriceOverviewResponse>(&b).map_err(SteamError::from),
+                Err(e) => Err(e),
+            };
+            let res = match parsed {
+                Ok(r) => r,
+                Err(e) => {
+                    eprintln!("Failed to get prices from steam for {} games: {}; skipping.", batch.len(), &e);
+                    failures.extend(batch.iter().map(|&id| (id, DetailsFailure::from(&e))));
+                    continue;
+                }
+            };
+
+            for (k, v) in res.results {
+                let id = GameId::try_from(k.as_ref()).map_err(|_| {
+                    SteamError::Conv(format!("Bad appid in price response: {}", &k))
+                })?;
+
+                if let Some(SteamPriceDetails::Priced { price_overview }) = v.data {
+                    prices.push(conv::extract_game_price(&id, &price_overview, &now));
+                }
+            }
+        }
+
+        Ok((prices, failures))
+    }
+}
+
 impl SteamWishlistHandling for SteamClient {
     fn get_wishlist(&self, account_id: &str) -> Result<Vec<WishlistedGame>> {
         let req = ureq::get(&format!("{}/IWishlistService/GetWishlist/v1/", self.api_host))
@@ -2,17 +2,17 @@ use super::*;
 
 use chrono::{TimeZone, Utc};
 
-use crate::models::game::{GameId, GameDetails};
+use crate::models::game::{Category, GameId, GameDetails, GamePrice, Genre, Platform, ReleasePrecision, ReleaseWindow};
 use crate::models::steam::*;
 
 // Some categories, including "Multi-player", indicating coop
-fn generic_coop_categories() -> Vec<Category> {
+fn generic_coop_categories() -> Vec<SteamCategory> {
     vec![
-        Category {
+        SteamCategory {
             id: 28,
             description: "Full controller support".to_string()
         },
-        Category {
+        SteamCategory {
             id: 1,
             description: "Multi-player".to_string()
         }
@@ -20,24 +20,37 @@ fn generic_coop_categories() -> Vec<Category> {
 }
 
 // Some categories, including "Shared/Split Screen Co-op", indicating local coop
-fn local_coop_categories() -> Vec<Category> {
+fn local_coop_categories() -> Vec<SteamCategory> {
     vec![
-        Category {
+        SteamCategory {
             id: 2,
             description: "Single-player".to_string()
         },
-        Category {
+        SteamCategory {
             id: 39,
             description: "Shared/Split Screen Co-op".to_string()
         }
     ]
 }
 
-fn details_fixture(categories: Vec<Category>, released: bool) -> SteamAppDetails {
+/// The categories as they're kept in game details
+fn expected_categories(categories: &[SteamCategory]) -> Vec<Category> {
+    categories.iter().map(|c| Category { id: c.id, description: c.description.clone() }).collect()
+}
+
+fn details_fixture(categories: Vec<SteamCategory>, released: bool) -> SteamAppDetails {
     SteamAppDetails {
+        app_type: Some("game".to_string()),
+        name: Some("Game Buying Simulator".to_string()),
+        is_free: Some(false),
         short_description: Some("Game buying simulator".to_string()),
+        header_image: Some("https://cdn.akamai.steamstatic.com/steam/apps/666666/header.jpg".to_string()),
         controller_support: Some("full".to_string()),
+        developers: vec!["Spendthrift Studios".to_string()],
+        publishers: vec!["Impulse Purchase Games".to_string()],
+        platforms: Some(Platforms { windows: true, mac: false, linux: true }),
         categories: categories,
+        genres: vec![SteamGenre { id: "28".to_string(), description: "Simulation".to_string() }],
         metacritic: Some(MetacriticScore { score: 66 }),
         release_date: Some(
             ReleaseDate {
@@ -56,7 +69,11 @@ fn convert_steam_details_released() {
 
     let expected = GameDetails {
         id: id.clone(),
+        name: fix.name.clone(),
+        app_type: fix.app_type.clone(),
+        is_free: Some(false),
         description: fix.short_description.clone(),
+        header_image: fix.header_image.clone(),
         controller_support: fix.controller_support.clone(),
         coop: false,
         local_coop: false,
@@ -64,6 +81,16 @@ fn convert_steam_details_released() {
         is_released: true,
         release_date: Some("17 Jan 2020".to_string()),
         release_estimate: Some(Utc.with_ymd_and_hms(2020, 1, 17, 0, 0, 0).unwrap()),
+        release_window: Some(ReleaseWindow {
+            earliest: Some(Utc.with_ymd_and_hms(2020, 1, 17, 0, 0, 0).unwrap()),
+            latest: Some(Utc.with_ymd_and_hms(2020, 1, 18, 0, 0, 0).unwrap()),
+            precision: ReleasePrecision::Day,
+        }),
+        categories: expected_categories(&fix.categories),
+        genres: vec![Genre { id: 28, description: "Simulation".to_string() }],
+        developers: fix.developers.clone(),
+        publishers: fix.publishers.clone(),
+        platforms: vec![Platform::Windows, Platform::Linux],
         recorded: now.clone()
     };
 
@@ -80,7 +107,11 @@ fn convert_steam_details_coming_soon() {
 
     let expected = GameDetails {
         id: id.clone(),
+        name: fix.name.clone(),
+        app_type: fix.app_type.clone(),
+        is_free: Some(false),
         description: fix.short_description.clone(),
+        header_image: fix.header_image.clone(),
         controller_support: fix.controller_support.clone(),
         coop: false,
         local_coop: false,
@@ -88,6 +119,16 @@ fn convert_steam_details_coming_soon() {
         is_released: false,
         release_date: Some("2079".to_string()),
         release_estimate: Some(Utc.with_ymd_and_hms(2080, 1, 1, 0, 0, 0).unwrap()),
+        release_window: Some(ReleaseWindow {
+            earliest: Some(Utc.with_ymd_and_hms(2079, 1, 1, 0, 0, 0).unwrap()),
+            latest: Some(Utc.with_ymd_and_hms(2080, 1, 1, 0, 0, 0).unwrap()),
+            precision: ReleasePrecision::Year,
+        }),
+        categories: expected_categories(&fix.categories),
+        genres: vec![Genre { id: 28, description: "Simulation".to_string() }],
+        developers: fix.developers.clone(),
+        publishers: fix.publishers.clone(),
+        platforms: vec![Platform::Windows, Platform::Linux],
         recorded: now.clone()
     };
 
@@ -104,7 +145,11 @@ fn convert_steam_details_remote_coop() {
 
     let expected = GameDetails {
         id: id.clone(),
+        name: fix.name.clone(),
+        app_type: fix.app_type.clone(),
+        is_free: Some(false),
         description: fix.short_description.clone(),
+        header_image: fix.header_image.clone(),
         controller_support: fix.controller_support.clone(),
         coop: true,
         local_coop: false,
@@ -112,6 +157,16 @@ fn convert_steam_details_remote_coop() {
         is_released: false,
         release_date: Some("2079".to_string()),
         release_estimate: Some(Utc.with_ymd_and_hms(2080, 1, 1, 0, 0, 0).unwrap()),
+        release_window: Some(ReleaseWindow {
+            earliest: Some(Utc.with_ymd_and_hms(2079, 1, 1, 0, 0, 0).unwrap()),
+            latest: Some(Utc.with_ymd_and_hms(2080, 1, 1, 0, 0, 0).unwrap()),
+            precision: ReleasePrecision::Year,
+        }),
+        categories: expected_categories(&fix.categories),
+        genres: vec![Genre { id: 28, description: "Simulation".to_string() }],
+        developers: fix.developers.clone(),
+        publishers: fix.publishers.clone(),
+        platforms: vec![Platform::Windows, Platform::Linux],
         recorded: now.clone()
     };
 
@@ -128,7 +183,11 @@ fn convert_steam_details_local_coop() {
 
     let expected = GameDetails {
         id: id.clone(),
+        name: fix.name.clone(),
+        app_type: fix.app_type.clone(),
+        is_free: Some(false),
         description: fix.short_description.clone(),
+        header_image: fix.header_image.clone(),
         controller_support: fix.controller_support.clone(),
         coop: true,
         local_coop: true,
@@ -136,6 +195,16 @@ fn convert_steam_details_local_coop() {
         is_released: false,
         release_date: Some("2079".to_string()),
         release_estimate: Some(Utc.with_ymd_and_hms(2080, 1, 1, 0, 0, 0).unwrap()),
+        release_window: Some(ReleaseWindow {
+            earliest: Some(Utc.with_ymd_and_hms(2079, 1, 1, 0, 0, 0).unwrap()),
+            latest: Some(Utc.with_ymd_and_hms(2080, 1, 1, 0, 0, 0).unwrap()),
+            precision: ReleasePrecision::Year,
+        }),
+        categories: expected_categories(&fix.categories),
+        genres: vec![Genre { id: 28, description: "Simulation".to_string() }],
+        developers: fix.developers.clone(),
+        publishers: fix.publishers.clone(),
+        platforms: vec![Platform::Windows, Platform::Linux],
         recorded: now.clone()
     };
 
@@ -145,54 +214,47 @@ fn convert_steam_details_local_coop() {
 }
 
 #[test]
-fn parse_release_date_exact_date() {
-    let expected = Utc.with_ymd_and_hms(2025, 6, 5, 0, 0, 0).unwrap();
-    let actual = parse_release_date("5 Jun, 2025");
-
-    assert_eq!(actual, Some(expected));
-}
+fn convert_steam_details_missing_metadata() {
+    let id = GameId { app_id: 666666 };
+    let fix = SteamAppDetails {
+        developers: vec![],
+        publishers: vec![],
+        platforms: None,
+        genres: vec![
+            SteamGenre { id: "23".to_string(), description: "Indie".to_string() },
+            SteamGenre { id: "".to_string(), description: "Mystery Genre".to_string() },
+        ],
+        ..details_fixture(vec![], true)
+    };
+    let now = Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap();
 
-#[test]
-fn parse_release_date_year() {
-    let expected = Utc.with_ymd_and_hms(2028, 1, 1, 0, 0, 0).unwrap();
-    let actual = parse_release_date("2027");
+    let actual = extract_game_details(&id, &fix, &now);
 
-    assert_eq!(actual, Some(expected));
+    // Genres without a usable ID are dropped rather than failing the whole game
+    assert_eq!(actual.genres, vec![Genre { id: 23, description: "Indie".to_string() }]);
+    assert_eq!(actual.developers, Vec::<String>::new());
+    assert_eq!(actual.platforms, vec![]);
 }
 
 #[test]
-fn parse_release_date_month_year() {
-    let expected = Utc.with_ymd_and_hms(2025, 5, 1, 0, 0, 0).unwrap();
-    let actual = parse_release_date("Apr 2025");
-
-    assert_eq!(actual, Some(expected));
-}
+fn convert_steam_price_overview() {
+    let id = GameId { app_id: 666666 };
+    let fix = PriceOverview {
+        currency: "GBP".to_string(),
+        initial: 2499,
+        final_price: 1874,
+        discount_percent: 25,
+    };
+    let now = Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap();
 
-#[test]
-fn parse_release_date_full_month_name_year() {
-    let inputs = vec![
-        "April 1999",
-        "December 2001",
-        "June 2025",
-        "October 2077",
-    ];
-    let expectations = vec![
-        Utc.with_ymd_and_hms(1999, 5, 1, 0, 0, 0).unwrap(),
-        Utc.with_ymd_and_hms(2002, 1, 1, 0, 0, 0).unwrap(),
-        Utc.with_ymd_and_hms(2025, 7, 1, 0, 0, 0).unwrap(),
-        Utc.with_ymd_and_hms(2077, 11, 1, 0, 0, 0).unwrap(),
-    ];
-
-    for (input, &expected) in inputs.iter().zip(expectations.iter()) {
-        let actual = parse_release_date(&input);
-        assert_eq!(actual, Some(expected), "should parse \"{}\" correctly", input)
-    }
-}
+    let expected = GamePrice {
+        id: id.clone(),
+        price: 1874,
+        discount_percent: 25,
+        recorded: now.clone(),
+    };
 
-#[test]
-fn parse_release_date_quarter() {
-    let expected = Utc.with_ymd_and_hms(2025, 7, 1, 0, 0, 0).unwrap();
-    let actual = parse_release_date("Q2 2025");
+    let actual = extract_game_price(&id, &fix, &now);
 
-    assert_eq!(actual, Some(expected));
+    assert_eq!(actual, expected);
 }
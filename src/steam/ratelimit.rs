@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod tests;
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tokio::time::sleep;
+
+/// Token bucket rate limiter shared between concurrent requests. Up to `capacity` requests may
+/// be made in a burst, after which tokens are replenished one at a time at a steady rate.
+#[derive(Debug)]
+pub struct RateLimiter {
+    capacity: f64,
+    interval: Duration,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// Create a full bucket of `capacity` tokens, replenishing one token every `interval`
+    pub fn new(capacity: u32, interval: Duration) -> RateLimiter {
+        RateLimiter {
+            capacity: f64::from(capacity),
+            interval,
+            state: Mutex::new(BucketState { tokens: f64::from(capacity), last_refill: Instant::now() }),
+        }
+    }
+
+    /// Create a limiter which allows at most `requests` requests in any window of the given
+    /// length. Half are allowed as an initial burst, and the other half trickle in over the
+    /// window, so a burst followed by a steady stream can never exceed the limit.
+    pub fn per_window(requests: u32, window: Duration) -> RateLimiter {
+        let capacity = (requests / 2).max(1);
+        RateLimiter::new(capacity, window / capacity)
+    }
+
+    // Add any tokens accrued since the last refill, up to the capacity of the bucket
+    fn refill(&self, state: &mut BucketState) {
+        let now = Instant::now();
+        let accrued = now.duration_since(state.last_refill).as_secs_f64() / self.interval.as_secs_f64();
+
+        state.tokens = (state.tokens + accrued).min(self.capacity);
+        state.last_refill = now;
+    }
+
+    /// Wait until a token is available and take it
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut state = self.state.lock().unwrap();
+                self.refill(&mut state);
+
+                if state.tokens >= 1.0 {
+                    state.tokens -= 1.0;
+                    return;
+                }
+                self.interval.mul_f64(1.0 - state.tokens)
+            };
+
+            sleep(wait).await;
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use super::*;
+
+use std::sync::Arc;
+
+use futures::future::join_all;
+
+#[tokio::test]
+async fn acquire_allows_burst_up_to_capacity() {
+    let limiter = RateLimiter::new(5, Duration::from_secs(60));
+    let start = Instant::now();
+
+    for _ in 0..5 {
+        limiter.acquire().await;
+    }
+
+    assert!(start.elapsed() < Duration::from_millis(50));
+}
+
+#[tokio::test]
+async fn acquire_waits_for_refill_when_empty() {
+    let limiter = RateLimiter::new(2, Duration::from_millis(50));
+    let start = Instant::now();
+
+    // Two from the initial burst, then each of the other two must wait for a token
+    for _ in 0..4 {
+        limiter.acquire().await;
+    }
+
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(100), "finished too quickly: {:?}", elapsed);
+    assert!(elapsed < Duration::from_millis(500), "took too long: {:?}", elapsed);
+}
+
+#[tokio::test]
+async fn acquire_shared_between_tasks() {
+    let limiter = Arc::new(RateLimiter::new(1, Duration::from_millis(30)));
+    let start = Instant::now();
+
+    let tasks = (0..4).map(|_| {
+        let l = limiter.clone();
+        async move { l.acquire().await }
+    });
+    join_all(tasks).await;
+
+    assert!(start.elapsed() >= Duration::from_millis(90));
+}
+
+#[test]
+fn per_window_splits_burst_and_rate() {
+    let limiter = RateLimiter::per_window(200, Duration::from_secs(300));
+
+    assert_eq!(limiter.capacity, 100.0);
+    assert_eq!(limiter.interval, Duration::from_secs(3));
+}
@@ -0,0 +1,296 @@
+#[cfg(test)]
+mod tests;
+
+use chrono::{DateTime, Months, NaiveDate, NaiveTime, Utc};
+
+use crate::models::game::{ReleasePrecision, ReleaseWindow};
+
+// Phrases which mean the store page doesn't give a date at all, in the languages the store
+// might respond in
+const UNKNOWN_PHRASES: [&str; 16] = [
+    "to be announced",
+    "to be determined",
+    "tba",
+    "tbd",
+    "coming soon",
+    "soon",
+    "when it's done",
+    "à venir",
+    "prochainement",
+    "bientôt disponible",
+    "demnächst",
+    "bald verfügbar",
+    "próximamente",
+    "em breve",
+    "prossimamente",
+    "скоро",
+];
+
+// Words which can be dropped without changing what's meant, e.g. "Coming in Q3 2025"
+const FILLER_WORDS: [&str; 32] = [
+    "coming", "release", "releasing", "releases", "launch", "launching", "planned", "expected",
+    "estimated", "est", "available", "in", "by", "for", "around", "of", "the", "on", "year",
+    "de", "del", "du", "en", "em", "im", "am", "um", "à", "a", "no", "nel", "во",
+];
+
+// Leading parts of month names and their abbreviations in English, French, German, Spanish,
+// Italian, Portuguese and Russian. Seasons are checked first, since "outono" (autumn) would
+// otherwise be taken for "outubro" (October).
+const MONTH_STEMS: [(u32, &[&str]); 12] = [
+    (1, &["jan", "ene", "gen", "янв"]),
+    (2, &["feb", "fév", "fev", "фев"]),
+    (3, &["mar", "mär", "мар"]),
+    (4, &["apr", "avr", "abr", "апр"]),
+    (5, &["may", "mai", "mag", "мая", "май"]),
+    (6, &["jun", "juin", "giu", "июн"]),
+    (7, &["jul", "juil", "lug", "июл"]),
+    (8, &["aug", "aoû", "aou", "ago", "авг"]),
+    (9, &["sep", "set", "сен"]),
+    (10, &["oct", "okt", "ott", "out", "окт"]),
+    (11, &["nov", "ноя"]),
+    (12, &["dec", "déc", "dez", "dic", "дек"]),
+];
+
+// Seasons, by the month they start in
+const SEASONS: [(u32, &[&str]); 4] = [
+    (3, &["spring", "printemps", "frühling", "frühjahr", "primavera", "весна", "весной"]),
+    (6, &["summer", "été", "ete", "sommer", "verano", "estate", "verão", "лето", "летом"]),
+    (9, &["autumn", "fall", "automne", "herbst", "otoño", "autunno", "outono", "осень", "осенью"]),
+    (12, &["winter", "hiver", "invierno", "inverno", "зима", "зимой"]),
+];
+
+// "Early", "mid" and "late", by the month the six month window they describe starts in
+const MODIFIERS: [(u32, &[&str]); 3] = [
+    (1, &["early", "début", "anfang", "principios", "inicios", "inizio", "início", "начало", "начале"]),
+    (4, &["mid", "mi", "mitte", "mediados", "metà"]),
+    (7, &["late", "end", "fin", "ende", "finales", "fine", "final", "конец", "конце"]),
+];
+
+const ORDINALS: [(u32, &[&str]); 4] = [
+    (1, &["1", "1st", "first", "1er", "premier", "erstes", "primer", "primo", "primeiro"]),
+    (2, &["2", "2nd", "second", "2e", "2ème", "zweites", "segundo", "secondo"]),
+    (3, &["3", "3rd", "third", "3e", "3ème", "drittes", "tercer", "terzo", "terceiro"]),
+    (4, &["4", "4th", "fourth", "4e", "4ème", "viertes", "cuarto", "quarto", "quarto"]),
+];
+
+const QUARTER_WORDS: [&str; 5] = ["quarter", "quartal", "trimestre", "квартал", "квартале"];
+
+const HALF_WORDS: [&str; 5] = ["half", "halbjahr", "hälfte", "semestre", "полугодие"];
+
+// Make an attempt to parse a release date into a DateTime estimate: the day itself for exact
+// dates, or the end of the month, quarter, half or year otherwise. See parse_release_window.
+pub fn parse_release_date(s: &str) -> Option<DateTime<Utc>> {
+    parse_release_window(s).and_then(|w| w.estimate())
+}
+
+// Parse a release date into the window the game is expected to be released in.
+// - Attempt to parse exact dates, in day-month or month-day order, or as ISO dates
+// - Treat months, quarters (e.g. "Q2 2025"), halves (e.g. "H1 2025"), seasons (e.g. "Spring
+//   2025"), "early", "mid" or "late" in a year, and years as the window from the start of that
+//   period up to the start of the next
+// - Treat "coming soon", "to be announced" etc. as unknown
+// - Understand month names and phrases in a handful of languages besides English, plus
+//   chinese, japanese and korean style dates like "2025年1月5日"
+// Returns None if the text can't be made sense of at all.
+pub fn parse_release_window(s: &str) -> Option<ReleaseWindow> {
+    let trimmed = s.trim().trim_end_matches(['!', '™']).trim();
+
+    if let Ok(d) = NaiveDate::parse_from_str(trimmed, "%Y-%m-%d") {
+        return Some(release_window(d, 0, ReleasePrecision::Day));
+    }
+
+    if trimmed.contains(['年', '년']) {
+        return parse_cjk_date(trimmed);
+    }
+
+    let lower = trimmed.to_lowercase();
+    let clean: String = {
+        lower
+            .chars()
+            .map(|c| if matches!(c, ',' | '.' | '-' | '/' | '(' | ')') { ' ' } else { c })
+            .collect()
+    };
+    let tokens: Vec<&str> = clean.split_whitespace().collect();
+
+    if UNKNOWN_PHRASES.contains(&tokens.join(" ").as_str()) {
+        return Some(ReleaseWindow::unknown());
+    }
+
+    let parts = ReleaseParts::from_tokens(&tokens)?;
+    parts.window()
+}
+
+// Dates like "2025年1月5日" or "2025년 1월", which give the year, then optionally month and day
+fn parse_cjk_date(s: &str) -> Option<ReleaseWindow> {
+    // Anything else, like "第一季度" for a quarter, isn't understood yet
+    let understood = |c: char| c.is_ascii_digit() || c.is_whitespace() || "年月日년월일".contains(c);
+    if !s.chars().all(understood) {
+        return None;
+    }
+
+    let numbers: Vec<u32> = {
+        s.split(|c: char| !c.is_ascii_digit())
+            .filter(|n| !n.is_empty())
+            .map(|n| n.parse::<u32>())
+            .collect::<Result<_, _>>()
+            .ok()?
+    };
+    let year = i32::try_from(*numbers.first()?).ok()?;
+
+    match numbers[1..] {
+        [] => Some(release_window(NaiveDate::from_ymd_opt(year, 1, 1)?, 12, ReleasePrecision::Year)),
+        [month] => Some(release_window(NaiveDate::from_ymd_opt(year, month, 1)?, 1, ReleasePrecision::Month)),
+        [month, day] => Some(release_window(NaiveDate::from_ymd_opt(year, month, day)?, 0, ReleasePrecision::Day)),
+        _ => None,
+    }
+}
+
+// The pieces of a release date found in its text, most of which will be missing
+#[derive(Debug, Default)]
+struct ReleaseParts {
+    year: Option<i32>,
+    month: Option<u32>,
+    day: Option<u32>,
+    quarter: Option<u32>,
+    half: Option<u32>,
+    season: Option<u32>,
+    modifier: Option<u32>,
+}
+
+impl ReleaseParts {
+    // Sort each word into the part of the date it gives, or give up on any word which doesn't
+    // fit rather than guess
+    fn from_tokens(tokens: &[&str]) -> Option<ReleaseParts> {
+        let mut parts = ReleaseParts::default();
+        let mut i = 0;
+
+        while i < tokens.len() {
+            let token = tokens[i];
+            let next = tokens.get(i + 1).copied();
+            i += 1;
+
+            // "3rd quarter", "1. Quartal", "second half" etc.
+            if let Some(n) = lookup(&ORDINALS, token) {
+                if next.is_some_and(|t| QUARTER_WORDS.contains(&t)) {
+                    set_once(&mut parts.quarter, n)?;
+                    i += 1;
+                    continue;
+                }
+                if next.is_some_and(|t| HALF_WORDS.contains(&t)) && n <= 2 {
+                    set_once(&mut parts.half, n)?;
+                    i += 1;
+                    continue;
+                }
+            }
+
+            if FILLER_WORDS.contains(&token) {
+                continue;
+            }
+
+            if let Some(n) = token.strip_prefix('q').and_then(|n| n.parse::<u32>().ok()) {
+                set_once(&mut parts.quarter, n).filter(|n| (1..=4).contains(n))?;
+                continue;
+            }
+            if let Some(n) = token.strip_prefix('h').and_then(|n| n.parse::<u32>().ok()) {
+                set_once(&mut parts.half, n).filter(|n| (1..=2).contains(n))?;
+                continue;
+            }
+
+            if token.len() == 4 {
+                if let Ok(year) = token.parse::<i32>() {
+                    set_once(&mut parts.year, year)?;
+                    continue;
+                }
+            }
+
+            // Days can be written "5", "05" or "5th"
+            let day = token.trim_end_matches(|c: char| c.is_alphabetic());
+            if (1..=2).contains(&day.len()) {
+                if let Ok(day) = day.parse::<u32>() {
+                    set_once(&mut parts.day, day)?;
+                    continue;
+                }
+            }
+
+            if let Some(start) = lookup(&SEASONS, token) {
+                set_once(&mut parts.season, start)?;
+                continue;
+            }
+            if let Some(start) = lookup(&MODIFIERS, token) {
+                set_once(&mut parts.modifier, start)?;
+                continue;
+            }
+
+            let month = MONTH_STEMS.iter().find(|(_, stems)| stems.iter().any(|s| token.starts_with(s)));
+            if let Some((month, _)) = month {
+                set_once(&mut parts.month, *month)?;
+                continue;
+            }
+
+            return None;
+        }
+
+        Some(parts)
+    }
+
+    // The window for the most specific period given, or None if there's no year, or a day
+    // without a month
+    fn window(&self) -> Option<ReleaseWindow> {
+        let year = self.year?;
+        let start = |month: u32| NaiveDate::from_ymd_opt(year, month, 1);
+
+        if self.day.is_some() && self.month.is_none() {
+            return None;
+        }
+
+        let window = match (self.month, self.day) {
+            (Some(month), Some(day)) => {
+                release_window(NaiveDate::from_ymd_opt(year, month, day)?, 0, ReleasePrecision::Day)
+            },
+            (Some(month), None) => release_window(start(month)?, 1, ReleasePrecision::Month),
+            _ => {
+                if let Some(q) = self.quarter {
+                    release_window(start((q - 1) * 3 + 1)?, 3, ReleasePrecision::Quarter)
+                } else if let Some(season) = self.season {
+                    release_window(start(season)?, 3, ReleasePrecision::Quarter)
+                } else if let Some(h) = self.half {
+                    release_window(start((h - 1) * 6 + 1)?, 6, ReleasePrecision::Half)
+                } else if let Some(modifier) = self.modifier {
+                    release_window(start(modifier)?, 6, ReleasePrecision::Half)
+                } else {
+                    release_window(start(1)?, 12, ReleasePrecision::Year)
+                }
+            },
+        };
+
+        Some(window)
+    }
+}
+
+// Find which entry in a table of words a token is one of
+fn lookup(table: &[(u32, &[&str])], token: &str) -> Option<u32> {
+    table.iter().find(|(_, words)| words.contains(&token)).map(|(n, _)| *n)
+}
+
+// Set part of a date, unless a different value was already found for it, e.g. "2025 2026"
+fn set_once<T: Copy + PartialEq>(part: &mut Option<T>, value: T) -> Option<T> {
+    match part {
+        Some(existing) if *existing != value => None,
+        _ => {
+            *part = Some(value);
+            Some(value)
+        },
+    }
+}
+
+// A window from midnight on the start date up to, but not including, midnight the given number of
+// months later, or the next day if that's zero
+fn release_window(start: NaiveDate, months: u32, precision: ReleasePrecision) -> ReleaseWindow {
+    let end = if months == 0 { start.succ_opt() } else { start.checked_add_months(Months::new(months)) };
+
+    ReleaseWindow {
+        earliest: Some(start.and_time(NaiveTime::MIN).and_utc()),
+        latest: end.map(|e| e.and_time(NaiveTime::MIN).and_utc()),
+        precision,
+    }
+}
@@ -0,0 +1,170 @@
+use super::*;
+
+use chrono::{TimeZone, Utc};
+
+use ReleasePrecision::*;
+
+type Expected = Option<(&'static str, &'static str, ReleasePrecision)>;
+
+// Release date text as seen in release_update.prev_text and new_text, with the window each
+// should be parsed as: earliest and latest dates, and precision. Unknown windows have no dates,
+// and None means the text isn't understood at all.
+const CORPUS: &[(&str, Expected)] = &[
+    // Exact dates
+    ("5 Jan, 2020", Some(("2020-01-05", "2020-01-06", Day))),
+    ("17 Jan 2020", Some(("2020-01-17", "2020-01-18", Day))),
+    ("31 Dec, 2025", Some(("2025-12-31", "2026-01-01", Day))),
+    ("Jan 5, 2020", Some(("2020-01-05", "2020-01-06", Day))),
+    ("January 5, 2020", Some(("2020-01-05", "2020-01-06", Day))),
+    ("Sept 12th, 2025", Some(("2025-09-12", "2025-09-13", Day))),
+    ("2025-03-14", Some(("2025-03-14", "2025-03-15", Day))),
+    ("29 Feb, 2028", Some(("2028-02-29", "2028-03-01", Day))),
+    // Months
+    ("Mar 2025", Some(("2025-03-01", "2025-04-01", Month))),
+    ("March 2025", Some(("2025-03-01", "2025-04-01", Month))),
+    ("December 2025", Some(("2025-12-01", "2026-01-01", Month))),
+    ("Early March 2026", Some(("2026-03-01", "2026-04-01", Month))),
+    ("Coming March 2026", Some(("2026-03-01", "2026-04-01", Month))),
+    // Quarters and seasons
+    ("Q2 2025", Some(("2025-04-01", "2025-07-01", Quarter))),
+    ("Q4 2025", Some(("2025-10-01", "2026-01-01", Quarter))),
+    ("Coming in Q3 2025", Some(("2025-07-01", "2025-10-01", Quarter))),
+    ("3rd Quarter 2025", Some(("2025-07-01", "2025-10-01", Quarter))),
+    ("Spring 2025", Some(("2025-03-01", "2025-06-01", Quarter))),
+    ("Summer 2025", Some(("2025-06-01", "2025-09-01", Quarter))),
+    ("Coming Summer 2026!", Some(("2026-06-01", "2026-09-01", Quarter))),
+    ("Fall 2025", Some(("2025-09-01", "2025-12-01", Quarter))),
+    ("Autumn 2025", Some(("2025-09-01", "2025-12-01", Quarter))),
+    ("Winter 2025", Some(("2025-12-01", "2026-03-01", Quarter))),
+    // Halves
+    ("H2 2025", Some(("2025-07-01", "2026-01-01", Half))),
+    ("1H 2026", None),
+    ("First half of 2026", Some(("2026-01-01", "2026-07-01", Half))),
+    ("2nd half 2026", Some(("2026-07-01", "2027-01-01", Half))),
+    ("Early 2026", Some(("2026-01-01", "2026-07-01", Half))),
+    ("Mid-2026", Some(("2026-04-01", "2026-10-01", Half))),
+    ("Late 2025", Some(("2025-07-01", "2026-01-01", Half))),
+    ("End of 2025", Some(("2025-07-01", "2026-01-01", Half))),
+    // Years
+    ("2026", Some(("2026-01-01", "2027-01-01", Year))),
+    ("Coming 2026", Some(("2026-01-01", "2027-01-01", Year))),
+    ("Planned release 2027", Some(("2027-01-01", "2028-01-01", Year))),
+    ("2026™", Some(("2026-01-01", "2027-01-01", Year))),
+    // No date given
+    ("Coming soon", Some(("", "", Unknown))),
+    ("Coming Soon™", Some(("", "", Unknown))),
+    ("To be announced", Some(("", "", Unknown))),
+    ("TBA", Some(("", "", Unknown))),
+    ("TBD", Some(("", "", Unknown))),
+    ("When it's done", Some(("", "", Unknown))),
+    ("Prochainement", Some(("", "", Unknown))),
+    ("Demnächst", Some(("", "", Unknown))),
+    ("Próximamente", Some(("", "", Unknown))),
+    // Other languages
+    ("5 janv. 2020", Some(("2020-01-05", "2020-01-06", Day))),
+    ("12 août 2025", Some(("2025-08-12", "2025-08-13", Day))),
+    ("5. Jan. 2020", Some(("2020-01-05", "2020-01-06", Day))),
+    ("3. Okt. 2025", Some(("2025-10-03", "2025-10-04", Day))),
+    ("5 ENE 2020", Some(("2020-01-05", "2020-01-06", Day))),
+    ("5 de ene. de 2020", Some(("2020-01-05", "2020-01-06", Day))),
+    ("14 mag 2025", Some(("2025-05-14", "2025-05-15", Day))),
+    ("5 янв. 2020", Some(("2020-01-05", "2020-01-06", Day))),
+    ("mars 2025", Some(("2025-03-01", "2025-04-01", Month))),
+    ("März 2025", Some(("2025-03-01", "2025-04-01", Month))),
+    ("outubro de 2025", Some(("2025-10-01", "2025-11-01", Month))),
+    ("1. Quartal 2026", Some(("2026-01-01", "2026-04-01", Quarter))),
+    ("Sommer 2025", Some(("2025-06-01", "2025-09-01", Quarter))),
+    ("Outono de 2025", Some(("2025-09-01", "2025-12-01", Quarter))),
+    ("Début 2026", Some(("2026-01-01", "2026-07-01", Half))),
+    ("2025年1月5日", Some(("2025-01-05", "2025-01-06", Day))),
+    ("2025 年 1 月 5 日", Some(("2025-01-05", "2025-01-06", Day))),
+    ("2025년 3월", Some(("2025-03-01", "2025-04-01", Month))),
+    ("2026 年", Some(("2026-01-01", "2027-01-01", Year))),
+    // Not understood
+    ("", None),
+    ("Q5 2026", None),
+    ("31 Feb, 2026", None),
+    ("5 2026", None),
+    ("Summer", None),
+    ("Out now on consoles", None),
+    ("Out when the stars align", None),
+    ("2025 2026", None),
+    ("2025 年第一季度", None),
+];
+
+fn expected_window(expected: Expected) -> Option<ReleaseWindow> {
+    let date = |d: &str| {
+        NaiveDate::parse_from_str(d, "%Y-%m-%d").ok().map(|d| d.and_time(NaiveTime::MIN).and_utc())
+    };
+
+    expected.map(|(earliest, latest, precision)| {
+        ReleaseWindow { earliest: date(earliest), latest: date(latest), precision }
+    })
+}
+
+#[test]
+fn parse_release_window_corpus() {
+    for (input, expected) in CORPUS {
+        let actual = parse_release_window(input);
+        assert_eq!(actual, expected_window(*expected), "should parse \"{}\" correctly", input);
+    }
+}
+
+#[test]
+fn parse_release_date_exact_date() {
+    let expected = Utc.with_ymd_and_hms(2025, 6, 5, 0, 0, 0).unwrap();
+    let actual = parse_release_date("5 Jun, 2025");
+
+    assert_eq!(actual, Some(expected));
+}
+
+#[test]
+fn parse_release_date_year() {
+    let expected = Utc.with_ymd_and_hms(2028, 1, 1, 0, 0, 0).unwrap();
+    let actual = parse_release_date("2027");
+
+    assert_eq!(actual, Some(expected));
+}
+
+#[test]
+fn parse_release_date_month_year() {
+    let expected = Utc.with_ymd_and_hms(2025, 5, 1, 0, 0, 0).unwrap();
+    let actual = parse_release_date("Apr 2025");
+
+    assert_eq!(actual, Some(expected));
+}
+
+#[test]
+fn parse_release_date_full_month_name_year() {
+    let inputs = vec![
+        "April 1999",
+        "December 2001",
+        "June 2025",
+        "October 2077",
+    ];
+    let expectations = vec![
+        Utc.with_ymd_and_hms(1999, 5, 1, 0, 0, 0).unwrap(),
+        Utc.with_ymd_and_hms(2002, 1, 1, 0, 0, 0).unwrap(),
+        Utc.with_ymd_and_hms(2025, 7, 1, 0, 0, 0).unwrap(),
+        Utc.with_ymd_and_hms(2077, 11, 1, 0, 0, 0).unwrap(),
+    ];
+
+    for (input, &expected) in inputs.iter().zip(expectations.iter()) {
+        let actual = parse_release_date(&input);
+        assert_eq!(actual, Some(expected), "should parse \"{}\" correctly", input)
+    }
+}
+
+#[test]
+fn parse_release_date_quarter() {
+    let expected = Utc.with_ymd_and_hms(2025, 7, 1, 0, 0, 0).unwrap();
+    let actual = parse_release_date("Q2 2025");
+
+    assert_eq!(actual, Some(expected));
+}
+
+#[test]
+fn parse_release_date_unknown() {
+    assert_eq!(parse_release_date("Coming soon"), None);
+    assert_eq!(parse_release_date("Whenever"), None);
+}
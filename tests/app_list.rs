@@ -0,0 +1,45 @@
+use chrono::{TimeZone, Utc};
+
+use rusteam::db;
+use rusteam::db::repo::{Repo, SteamGamesHandling};
+use rusteam::models::game::{AppType, GameId, SteamApp};
+
+#[tokio::test]
+async fn test_upsert_steam_apps() {
+    let conn_str = "host=localhost port=15432 user=tests password=test_admin dbname=rusteam_test";
+    let mut db_client = db::connect(conn_str).await;
+    db::migrate(&mut db_client).await;
+
+    let repo = Repo::new(db_client);
+    let id = GameId { app_id: 8500001 };
+
+    let app = SteamApp {
+        id,
+        name: "Working Title".to_string(),
+        app_type: AppType::Game,
+        last_modified: Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap(),
+    };
+    repo.upsert_steam_apps(std::slice::from_ref(&app)).await.unwrap();
+
+    // Apps which change in the catalogue should be updated rather than skipped
+    let renamed = SteamApp {
+        name: "Final Title".to_string(),
+        last_modified: Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap(),
+        ..app
+    };
+    assert_eq!(repo.upsert_steam_apps(&[renamed]).await.unwrap(), 1);
+
+    let names = repo.get_game_names_by_id(&[id]).await.unwrap();
+    assert_eq!(names.get(&id).map(|n| n.as_str()), Some("Final Title"));
+
+    // No other tests sync hardware, so its high-water mark is ours to check
+    let mark = Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap();
+    repo.set_app_list_mark(AppType::Hardware, &mark).await.unwrap();
+
+    let marks = repo.get_app_list_marks().await.unwrap();
+    assert_eq!(marks.get(&AppType::Hardware), Some(&mark));
+
+    let check = db::connect(conn_str).await;
+    check.execute("DELETE FROM steam_game WHERE app_id = 8500001", &[]).await.unwrap();
+    check.execute("DELETE FROM app_list_sync WHERE app_type = 'hardware'", &[]).await.unwrap();
+}
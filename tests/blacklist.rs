@@ -0,0 +1,78 @@
+use chrono::{TimeDelta, Utc};
+
+use rusteam::config;
+use rusteam::db;
+use rusteam::db::repo::{GameDetailsBlacklistHandling, GameDetailsHandling, OwnedGamesHandling, Repo};
+use rusteam::models::game::GameId;
+
+// Games used only by this test, so as not to disturb the tracked games in other tests
+const FRESH_FAILURE: i64 = 9000001;
+const BACKED_OFF: i64 = 9000002;
+const BACKOFF_ELAPSED: i64 = 9000003;
+const RETIRED: i64 = 9000004;
+
+async fn connect() -> Repo {
+    let conf = config::read(Some(&"test/test-config.toml".to_string()));
+    let mut db_client = db::connect(&conf.db.connection_string()).await;
+    db::migrate(&mut db_client).await;
+    Repo::new(db_client)
+}
+
+async fn blacklist_game(repo: &Repo, app_id: i64, failure_count: u32, hours_ago: i64) {
+    let game = GameId::from(app_id);
+    let last_failed = Utc::now() - TimeDelta::hours(hours_ago);
+
+    repo.insert_owned_games(&[game]).await.unwrap();
+    for _ in 0..failure_count {
+        repo.mark_game_detail_failures(&[game], &last_failed).await;
+    }
+}
+
+/// Of the games used by this test, those get_games_missing_details would fetch now
+async fn missing_test_games(repo: &Repo) -> Vec<i64> {
+    repo.get_games_missing_details()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(Into::<i64>::into)
+        .filter(|id| (FRESH_FAILURE..=RETIRED).contains(id))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_blacklist() {
+    let repo = connect().await;
+    repo.begin_transaction().await.unwrap();
+
+    // One failure waits 6 hours, three failures wait 24 hours, five failures are retired
+    blacklist_game(&repo, FRESH_FAILURE, 1, 1).await;
+    blacklist_game(&repo, BACKED_OFF, 3, 12).await;
+    blacklist_game(&repo, BACKOFF_ELAPSED, 3, 25).await;
+    blacklist_game(&repo, RETIRED, 5, 1000).await;
+
+    let missing = missing_test_games(&repo).await;
+
+    let entries = repo.get_blacklist().await.unwrap();
+    let entry = |app_id: i64| entries.iter().find(|e| e.id == GameId::from(app_id)).cloned().unwrap();
+    let retired = entry(RETIRED);
+    let backed_off = entry(BACKED_OFF);
+
+    // Resetting a retired game should make it eligible again
+    let reset = repo.delete_blacklist_entries(&[GameId::from(RETIRED)]).await.unwrap();
+    let missing_after_reset = missing_test_games(&repo).await;
+    let still_blacklisted = repo.get_blacklist().await.unwrap().iter().any(|e| e.id == GameId::from(RETIRED));
+
+    repo.rollback_transaction().await.unwrap();
+
+    assert_eq!(missing, vec![BACKOFF_ELAPSED]);
+
+    assert!(retired.is_retired());
+    assert_eq!(retired.failure_count, 5);
+    assert_eq!(retired.retry_after(), None);
+    assert_eq!(backed_off.failure_count, 3);
+    assert!(backed_off.retry_after().unwrap() > Utc::now());
+
+    assert_eq!(reset, 1);
+    assert!(missing_after_reset.contains(&RETIRED));
+    assert!(!still_blacklisted);
+}
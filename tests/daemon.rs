@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use chrono::{TimeDelta, TimeZone, Utc};
+
+use rusteam::config;
+use rusteam::daemon::{DaemonError, Schedule};
+use rusteam::models::sync::SyncPhase;
+
+#[test]
+fn test_schedule() {
+    let start = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+    let intervals = HashMap::from([
+        (SyncPhase::SteamGames, TimeDelta::days(1)),
+        (SyncPhase::PlayedGames, TimeDelta::hours(1)),
+        (SyncPhase::GameDetails, TimeDelta::hours(4)),
+    ]);
+    // The app list was synced recently, but nothing else has been synced yet
+    let last_synced = HashMap::from([(SyncPhase::SteamGames, start - TimeDelta::hours(2))]);
+
+    let mut schedule = Schedule::new(intervals, TimeDelta::minutes(15), &last_synced, &start);
+
+    assert_eq!(schedule.due(&start), vec![SyncPhase::GameDetails, SyncPhase::PlayedGames]);
+    assert_eq!(schedule.next_due(), Some(start));
+
+    schedule.record(SyncPhase::GameDetails, true, &start);
+    schedule.record(SyncPhase::PlayedGames, false, &start);
+
+    // Failed phases are retried sooner than their usual interval
+    assert_eq!(schedule.next_due(), Some(start + TimeDelta::minutes(15)));
+    assert_eq!(schedule.due(&(start + TimeDelta::minutes(15))), vec![SyncPhase::PlayedGames]);
+
+    schedule.record(SyncPhase::PlayedGames, true, &(start + TimeDelta::minutes(15)));
+
+    assert_eq!(schedule.due(&(start + TimeDelta::hours(1))), vec![]);
+    assert_eq!(
+        schedule.due(&(start + TimeDelta::hours(22))),
+        vec![SyncPhase::SteamGames, SyncPhase::GameDetails, SyncPhase::PlayedGames]
+    );
+}
+
+#[test]
+fn test_schedule_from_config() {
+    let now = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+    let last_synced = {
+        SyncPhase::ALL.into_iter().map(|p| (p, now - TimeDelta::minutes(90))).collect()
+    };
+
+    let conf = config::Daemon {
+        interval_minutes: HashMap::from([
+            ("prices".to_string(), 30),
+            ("notion".to_string(), 0),
+        ]),
+        retry_minutes: 15,
+    };
+    let schedule = Schedule::from_config(&conf, &last_synced, &now).unwrap();
+
+    // Defaults apply to anything not configured, and notion has been switched off
+    assert_eq!(
+        schedule.due(&now),
+        vec![SyncPhase::Wishlist, SyncPhase::Prices, SyncPhase::PlayedGames]
+    );
+
+    let conf = config::Daemon {
+        interval_minutes: HashMap::from([("everything".to_string(), 30)]),
+        retry_minutes: 15,
+    };
+    assert!(matches!(
+        Schedule::from_config(&conf, &last_synced, &now),
+        Err(DaemonError::UnknownPhase(p)) if p == "everything"
+    ));
+}
+
+#[tokio::test]
+async fn test_reconnect_failure() {
+    // Nothing listens here, so the daemon should be told rather than brought down
+    let res = rusteam::db::try_connect("host=localhost port=1 user=tests password=test_admin dbname=rusteam_test").await;
+
+    assert!(res.is_err());
+}
@@ -0,0 +1,259 @@
+use std::sync::{Arc, Mutex};
+
+use chrono::{TimeDelta, TimeZone, Utc};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+
+use rusteam::config::{Email, SmtpSecurity};
+use rusteam::db;
+use rusteam::db::repo::{Repo, SyncEventHandling};
+use rusteam::db::sync::SyncEvent;
+use rusteam::models::game::{GameId, ReleaseChange};
+use rusteam::notify::{self, Notification, Notifier};
+use rusteam::notify::email::{Digest, EmailNotifier};
+
+/// A stand-in SMTP server which accepts any mail, recording the raw messages it receives
+async fn start_smtp_server() -> (u16, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let received: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(vec![]));
+    let messages = received.clone();
+
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (read, mut write) = stream.into_split();
+            let mut lines = BufReader::new(read).lines();
+            let messages = messages.clone();
+
+            tokio::spawn(async move {
+                write.write_all(b"220 localhost ESMTP\r\n").await.unwrap();
+
+                let mut data: Option<String> = None;
+                while let Ok(Some(line)) = lines.next_line().await {
+                    if let Some(msg) = data.as_mut() {
+                        if line == "." {
+                            messages.lock().unwrap().push(data.take().unwrap());
+                            write.write_all(b"250 OK\r\n").await.unwrap();
+                        } else {
+                            msg.push_str(&line);
+                            msg.push('\n');
+                        }
+                        continue;
+                    }
+
+                    let reply: &[u8] = match line.split(' ').next().unwrap().to_uppercase().as_str() {
+                        "EHLO" | "HELO" => b"250 localhost\r\n",
+                        "DATA" => {
+                            data = Some(String::new());
+                            b"354 Go ahead\r\n"
+                        },
+                        "QUIT" => {
+                            write.write_all(b"221 Bye\r\n").await.unwrap();
+                            break;
+                        },
+                        _ => b"250 OK\r\n",
+                    };
+                    write.write_all(reply).await.unwrap();
+                }
+            });
+        }
+    });
+
+    (port, received)
+}
+
+fn email_config(name: &str, port: u16, min_interval_hours: Option<i64>) -> Email {
+    Email {
+        name: name.to_string(),
+        smtp_host: "127.0.0.1".to_string(),
+        smtp_port: port,
+        security: SmtpSecurity::None,
+        username: None,
+        password: None,
+        from: "rusteam <rusteam@example.com>".to_string(),
+        to: vec!["me@example.com".to_string()],
+        subject: "Weekly rusteam digest".to_string(),
+        min_interval_hours,
+        events: None,
+    }
+}
+
+fn notifications_fixture() -> Vec<Notification> {
+    let recorded = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+    let notification = |event_id: i64, game_name: &str, event: SyncEvent| Notification {
+        event_id, game_name: game_name.to_string(), recorded, event
+    };
+
+    vec![
+        notification(
+            1,
+            "Unearthed Myths 7",
+            SyncEvent::ReleaseDateUpdated {
+                game: GameId { app_id: 666666 },
+                prev_text: "Q1 2025".to_string(),
+                prev_date: Some(Utc.with_ymd_and_hms(2025, 3, 31, 0, 0, 0).unwrap()),
+                new_text: "2026".to_string(),
+                new_date: Some(Utc.with_ymd_and_hms(2026, 12, 31, 0, 0, 0).unwrap()),
+                change: ReleaseChange::Postponed,
+            }
+        ),
+        notification(2, "Paint Drying Tycoon <3>", SyncEvent::Released { game: GameId { app_id: 654321 } }),
+        notification(
+            3,
+            "Final Fantasy MMLXVII",
+            SyncEvent::ReleaseDateUpdated {
+                game: GameId { app_id: 1337 },
+                prev_text: "Coming soon".to_string(),
+                prev_date: None,
+                new_text: "18 Sep, 2025".to_string(),
+                new_date: Some(Utc.with_ymd_and_hms(2025, 9, 18, 0, 0, 0).unwrap()),
+                change: ReleaseChange::Narrowed,
+            }
+        ),
+        notification(
+            4,
+            "Game Buying Simulator 2024",
+            SyncEvent::OnSale { game: GameId { app_id: 666 }, price: 999, discount_percent: 50 }
+        ),
+    ]
+}
+
+#[test]
+fn test_digest() {
+    let notifications = notifications_fixture();
+    let digest = Digest::new(&notifications);
+
+    let expected_text = "\
+Released (1)
+  - 🚀 Paint Drying Tycoon <3> is newly released!
+    https://store.steampowered.com/app/654321
+
+Delayed (1)
+  - 🐌 Unearthed Myths 7 has been delayed: \"Q1 2025\" -> \"2026\"
+    https://store.steampowered.com/app/666666
+
+Narrowed down or brought forward (1)
+  - 📌 Release date narrowed down for Final Fantasy MMLXVII: \"Coming soon\" -> \"18 Sep, 2025\"
+    https://store.steampowered.com/app/1337
+
+On sale (1)
+  - 💸 Game Buying Simulator 2024 is on sale: 50% off, now £9.99
+    https://store.steampowered.com/app/666
+
+";
+    assert_eq!(digest.text(), expected_text);
+
+    let html = digest.html();
+    assert!(html.contains("<h2>Delayed (1)</h2>"));
+    assert!(html.contains(
+        "<li>🚀 <a href=\"https://store.steampowered.com/app/654321\">Paint Drying Tycoon &lt;3&gt;</a> is newly released!</li>"
+    ));
+    assert!(!html.contains("Other release date changes"));
+
+    assert!(Digest::new(&[]).is_empty());
+}
+
+#[tokio::test]
+async fn test_email_notifier() {
+    let (port, received) = start_smtp_server().await;
+
+    let email = EmailNotifier::from_config(&email_config("test-email", port, None));
+    email.notify(&notifications_fixture()).await.unwrap();
+
+    // Nothing to report, so nothing should be sent
+    email.notify(&[]).await.unwrap();
+
+    let messages = received.lock().unwrap().clone();
+    assert_eq!(messages.len(), 1);
+    assert!(messages[0].contains("Subject: Weekly rusteam digest"));
+    assert!(messages[0].contains("To: me@example.com"));
+    assert!(messages[0].contains("multipart/alternative"));
+    assert!(messages[0].contains("text/plain"));
+    assert!(messages[0].contains("text/html"));
+}
+
+#[tokio::test]
+async fn test_email_digest_interval() {
+    let conn_str = "host=localhost port=15432 user=tests password=test_admin dbname=rusteam_test";
+    let mut db_client = db::connect(conn_str).await;
+    db::migrate(&mut db_client).await;
+
+    // Start from a clean slate, as the interval depends on when this notifier last sent anything
+    db_client
+        .execute("DELETE FROM sync_event_delivery WHERE notifier = 'test-weekly-email'", &[])
+        .await
+        .unwrap();
+    let repo = Repo::new(db_client);
+
+    let (port, received) = start_smtp_server().await;
+    let notifiers: Vec<Box<dyn Notifier>> = vec![
+        Box::new(EmailNotifier::from_config(&email_config("test-weekly-email", port, Some(168)))),
+    ];
+
+    repo.insert_sync_events(None, &[SyncEvent::Released { game: GameId { app_id: 8200001 } }]).await.unwrap();
+    notify::dispatch(&repo, &notifiers, TimeDelta::days(1)).await;
+
+    // A digest has just been sent, so new events should wait for the next one
+    repo.insert_sync_events(None, &[SyncEvent::Released { game: GameId { app_id: 8200002 } }]).await.unwrap();
+    notify::dispatch(&repo, &notifiers, TimeDelta::days(1)).await;
+
+    assert_eq!(received.lock().unwrap().len(), 1);
+
+    let since = Utc::now() - TimeDelta::hours(1);
+    let held_back: Vec<GameId> = {
+        repo.get_undelivered_events("test-weekly-email", &since)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| *e.event.game())
+            .collect()
+    };
+    assert_eq!(held_back, vec![GameId { app_id: 8200002 }]);
+}
+
+#[tokio::test]
+async fn test_email_digest_interval_ignores_filtered_events() {
+    let conn_str = "host=localhost port=15432 user=tests password=test_admin dbname=rusteam_test";
+    let mut db_client = db::connect(conn_str).await;
+    db::migrate(&mut db_client).await;
+    let check = db::connect(conn_str).await;
+
+    check
+        .execute("DELETE FROM sync_event_delivery WHERE notifier = 'test-releases-email'", &[])
+        .await
+        .unwrap();
+    let repo = Repo::new(db_client);
+
+    let (port, received) = start_smtp_server().await;
+    let config = Email {
+        events: Some(vec!["released".to_string()]),
+        ..email_config("test-releases-email", port, Some(168))
+    };
+    let notifiers: Vec<Box<dyn Notifier>> = vec![Box::new(EmailNotifier::from_config(&config))];
+
+    // The last digest went out over a week ago
+    repo.insert_sync_events(None, &[SyncEvent::Released { game: GameId { app_id: 8200003 } }]).await.unwrap();
+    notify::dispatch(&repo, &notifiers, TimeDelta::days(1)).await;
+    check
+        .execute(
+            "UPDATE sync_event_delivery SET delivered = delivered - INTERVAL '8 days' WHERE notifier = 'test-releases-email'",
+            &[],
+        )
+        .await
+        .unwrap();
+
+    // Only events the notifier leaves out, so nothing is sent and the next digest is still due
+    let sale = SyncEvent::OnSale { game: GameId { app_id: 8200004 }, price: 999, discount_percent: 50 };
+    repo.insert_sync_events(None, &[sale]).await.unwrap();
+    notify::dispatch(&repo, &notifiers, TimeDelta::days(1)).await;
+    assert_eq!(received.lock().unwrap().len(), 1);
+
+    repo.insert_sync_events(None, &[SyncEvent::Released { game: GameId { app_id: 8200005 } }]).await.unwrap();
+    notify::dispatch(&repo, &notifiers, TimeDelta::days(1)).await;
+
+    let messages = received.lock().unwrap().clone();
+    assert_eq!(messages.len(), 2);
+    assert!(messages[1].contains("8200005"));
+    assert!(!messages[1].contains("8200004"));
+}
@@ -0,0 +1,89 @@
+use chrono::{TimeDelta, TimeZone, Utc};
+
+use rusteam::cli::events::parse_since;
+use rusteam::config;
+use rusteam::db;
+use rusteam::db::repo::{Repo, SyncEventHandling};
+use rusteam::db::sync::SyncEvent;
+use rusteam::models::game::{GameId, ReleaseChange};
+
+fn config_file() -> String {
+    "test/test-config.toml".to_string()
+}
+
+async fn repo() -> Repo {
+    let conf = config::read(Some(&config_file()));
+    let mut db_client = db::connect(&conf.db.connection_string()).await;
+    db::migrate(&mut db_client).await;
+    Repo::new(db_client)
+}
+
+#[tokio::test]
+async fn test_sync_events_replay_and_delivery() {
+    let repo = repo().await;
+    let since = Utc::now() - TimeDelta::seconds(1);
+
+    // Games used only by this test, so as not to pick up events from other tests
+    let test_games = [GameId { app_id: 8000001 }, GameId { app_id: 8000002 }];
+    let events = vec![
+        SyncEvent::ReleaseDateUpdated {
+            game: test_games[0],
+            prev_text: "Coming soon".to_string(),
+            prev_date: None,
+            new_text: "18 Sep, 2025".to_string(),
+            new_date: Some(Utc.with_ymd_and_hms(2025, 9, 18, 0, 0, 0).unwrap()),
+            change: ReleaseChange::Narrowed,
+        },
+        SyncEvent::Released { game: test_games[1] },
+    ];
+
+    repo.insert_sync_events(None, &events).await.unwrap();
+
+    let replayed: Vec<SyncEvent> = {
+        repo.get_sync_events_since(&since)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| e.event)
+            .filter(|e| test_games.contains(e.game()))
+            .collect()
+    };
+    assert_eq!(replayed, events);
+
+    // Each notifier should only be given events it hasn't already delivered
+    let undelivered = |notifier: &'static str| {
+        let repo = &repo;
+        async move {
+            repo.get_undelivered_events(notifier, &since)
+                .await
+                .unwrap()
+                .into_iter()
+                .filter(|e| test_games.contains(e.event.game()))
+                .collect::<Vec<_>>()
+        }
+    };
+
+    let pending = undelivered("test-webhook").await;
+    assert_eq!(pending.len(), 2);
+
+    repo.mark_events_delivered("test-webhook", &[pending[0].id]).await.unwrap();
+
+    let pending: Vec<SyncEvent> = undelivered("test-webhook").await.into_iter().map(|e| e.event).collect();
+    assert_eq!(pending, vec![SyncEvent::Released { game: test_games[1] }]);
+    assert_eq!(undelivered("test-email").await.len(), 2);
+}
+
+#[test]
+fn test_parse_since() {
+    let now = Utc.with_ymd_and_hms(2024, 5, 10, 12, 0, 0).unwrap();
+
+    assert_eq!(parse_since("2024-05-01", &now), Some(Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap()));
+    assert_eq!(parse_since("12h", &now), Some(Utc.with_ymd_and_hms(2024, 5, 10, 0, 0, 0).unwrap()));
+    assert_eq!(parse_since("7d", &now), Some(Utc.with_ymd_and_hms(2024, 5, 3, 12, 0, 0).unwrap()));
+    assert_eq!(parse_since("1w", &now), Some(Utc.with_ymd_and_hms(2024, 5, 3, 12, 0, 0).unwrap()));
+    assert_eq!(parse_since("soon", &now), None);
+    assert_eq!(parse_since("", &now), None);
+    assert_eq!(parse_since("3д", &now), None);
+    assert_eq!(parse_since("5é", &now), None);
+    assert_eq!(parse_since("д", &now), None);
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use chrono::{TimeZone, Utc};
+
+use rusteam::cli::feed::RunFeed;
+use rusteam::config;
+use rusteam::db;
+use rusteam::db::repo::{ReleaseUpdateHandling, Repo, SteamGamesHandling, SyncEventHandling};
+use rusteam::db::sync::SyncEvent;
+use rusteam::feed::{render_atom, FeedEntry};
+use rusteam::models::game::{GameId, ReleaseChange};
+
+// Games used only by this test, so other feed entries can be told apart
+const DELAYED_GAME: u32 = 8300001;
+const RELEASED_GAME: u32 = 8300002;
+
+fn config_file() -> String {
+    "test/test-config.toml".to_string()
+}
+
+#[test]
+fn test_render_atom() {
+    let entries = vec![
+        FeedEntry {
+            id: "tag:rusteam,2024:release_update/1".to_string(),
+            title: "🐌 Delayed: Paint Drying Tycoon <3>".to_string(),
+            link: "https://store.steampowered.com/app/654321".to_string(),
+            summary: "Release date for Paint Drying Tycoon <3> changed from \"Q1 2025\" to \"2026\"".to_string(),
+            updated: Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap(),
+        },
+        FeedEntry {
+            id: "tag:rusteam,2024:sync_event/7".to_string(),
+            title: "🚀 Unearthed Myths 7 is out now".to_string(),
+            link: "https://store.steampowered.com/app/666666".to_string(),
+            summary: "🚀 Unearthed Myths 7 is newly released!".to_string(),
+            updated: Utc.with_ymd_and_hms(2024, 5, 2, 12, 0, 0).unwrap(),
+        },
+    ];
+    let now = Utc.with_ymd_and_hms(2024, 5, 3, 0, 0, 0).unwrap();
+
+    let expected = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <id>tag:rusteam,2024:feed</id>
+  <title>Release news</title>
+  <updated>2024-05-02T12:00:00+00:00</updated>
+  <author><name>rusteam</name></author>
+  <link rel="self" href="https://example.com/feed.xml"/>
+  <entry>
+    <id>tag:rusteam,2024:sync_event/7</id>
+    <title>🚀 Unearthed Myths 7 is out now</title>
+    <link href="https://store.steampowered.com/app/666666"/>
+    <updated>2024-05-02T12:00:00+00:00</updated>
+    <summary>🚀 Unearthed Myths 7 is newly released!</summary>
+  </entry>
+  <entry>
+    <id>tag:rusteam,2024:release_update/1</id>
+    <title>🐌 Delayed: Paint Drying Tycoon &lt;3&gt;</title>
+    <link href="https://store.steampowered.com/app/654321"/>
+    <updated>2024-05-01T12:00:00+00:00</updated>
+    <summary>Release date for Paint Drying Tycoon &lt;3&gt; changed from &quot;Q1 2025&quot; to &quot;2026&quot;</summary>
+  </entry>
+</feed>
+"#;
+
+    assert_eq!(render_atom("Release news", Some("https://example.com/feed.xml"), &entries, &now), expected);
+}
+
+#[tokio::test]
+async fn test_feed_command() {
+    let conf = config::read(Some(&config_file()));
+    let mut db_client = db::connect(&conf.db.connection_string()).await;
+    db::migrate(&mut db_client).await;
+    let repo = Repo::new(db_client);
+
+    repo
+        .insert_steam_games(HashMap::from([(DELAYED_GAME, "Slowpoke Simulator"), (RELEASED_GAME, "Finally Out")]))
+        .await
+        .unwrap();
+    repo
+        .insert_release_update(
+            &GameId { app_id: DELAYED_GAME },
+            "Q1 2025",
+            &Some(Utc.with_ymd_and_hms(2025, 3, 31, 0, 0, 0).unwrap()),
+            "2026",
+            &Some(Utc.with_ymd_and_hms(2026, 12, 31, 0, 0, 0).unwrap()),
+            ReleaseChange::Postponed,
+        )
+        .await
+        .unwrap();
+    repo
+        .insert_sync_events(None, &[SyncEvent::Released { game: GameId { app_id: RELEASED_GAME } }])
+        .await
+        .unwrap();
+
+    let output = std::env::temp_dir().join("rusteam-test-feed.xml");
+    let cmd = RunFeed {
+        config_file: Some(PathBuf::from(config_file())),
+        output: Some(output.clone()),
+        days: Some(1),
+    };
+
+    cmd.run().await;
+    let first = std::fs::read_to_string(&output).unwrap();
+
+    assert!(first.contains("<title>🐌 Delayed: Slowpoke Simulator</title>"));
+    assert!(first.contains(&format!("<link href=\"https://store.steampowered.com/app/{}\"/>", DELAYED_GAME)));
+    assert!(first.contains("<title>🚀 Finally Out is out now</title>"));
+
+    // Entries should keep the same IDs each time the feed is written
+    cmd.run().await;
+    let second = std::fs::read_to_string(&output).unwrap();
+
+    let ids = |feed: &str| -> Vec<String> {
+        feed.lines()
+            .filter(|l| l.trim_start().starts_with("<id>"))
+            .map(|l| l.trim().to_string())
+            .collect()
+    };
+    assert_eq!(ids(&first), ids(&second));
+
+    std::fs::remove_file(&output).unwrap();
+}
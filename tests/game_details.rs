@@ -0,0 +1,195 @@
+use chrono::{NaiveDateTime, TimeZone, Utc};
+
+use rusteam::db;
+use rusteam::db::repo::{GameDetailsHandling, Repo};
+use rusteam::models::game::{
+    Category, GameDetails, GameId, Genre, Platform, ReleaseDateText, ReleasePrecision, ReleaseWindow,
+};
+
+const CONN_STR: &str = "host=localhost port=15432 user=tests password=test_admin dbname=rusteam_test";
+
+fn details_fixture(developers: &[&str], platforms: Vec<Platform>) -> GameDetails {
+    GameDetails {
+        id: GameId { app_id: 8700001 },
+        name: Some("Sequel Simulator".to_string()),
+        app_type: Some("game".to_string()),
+        is_free: Some(false),
+        description: None,
+        header_image: Some(GameId { app_id: 8700001 }.header_image_url()),
+        controller_support: None,
+        coop: false,
+        local_coop: false,
+        metacritic_percent: None,
+        is_released: false,
+        release_date: Some("2026".to_string()),
+        release_estimate: None,
+        release_window: None,
+        categories: vec![
+            Category { id: 2, description: "Single-player".to_string() },
+            Category { id: 44, description: "Remote Play Together".to_string() },
+        ],
+        genres: vec![Genre { id: 28, description: "Simulation".to_string() }],
+        developers: developers.iter().map(|d| d.to_string()).collect(),
+        publishers: vec!["Sequel Publishing".to_string()],
+        platforms,
+        recorded: Utc::now(),
+    }
+}
+
+#[tokio::test]
+async fn test_update_game_metadata() {
+    let mut db_client = db::connect(CONN_STR).await;
+    db::migrate(&mut db_client).await;
+
+    let repo = Repo::new(db_client);
+    let check = db::connect(CONN_STR).await;
+
+    let companies_q = r#"
+        SELECT gc.role, c.name
+        FROM game_company gc JOIN company c ON gc.company_id = c.id
+        WHERE gc.app_id = 8700001
+        ORDER BY gc.role, c.name
+    "#;
+    let platforms_q = "SELECT platform FROM game_platform WHERE app_id = 8700001 ORDER BY platform";
+
+    let first = details_fixture(&["First Studio", "Port House"], vec![Platform::Windows, Platform::Mac]);
+    repo.insert_game_details(std::slice::from_ref(&first)).await.unwrap();
+    repo.update_game_metadata(&[first]).await.unwrap();
+
+    // Later details replace what was recorded before, rather than adding to it
+    let second = details_fixture(&["First Studio"], vec![Platform::Windows, Platform::Linux]);
+    repo.update_game_metadata(&[second]).await.unwrap();
+
+    let companies: Vec<(String, String)> = {
+        check.query(companies_q, &[]).await.unwrap().iter().map(|r| (r.get(0), r.get(1))).collect()
+    };
+    let platforms: Vec<String> = {
+        check.query(platforms_q, &[]).await.unwrap().iter().map(|r| r.get(0)).collect()
+    };
+    let genres: Vec<String> = {
+        check
+            .query(
+                "SELECT g.description FROM game_genre gg JOIN genre g ON gg.genre_id = g.id WHERE gg.app_id = 8700001",
+                &[]
+            )
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| r.get(0))
+            .collect()
+    };
+    let categories: Vec<String> = {
+        check
+            .query(
+                r#"
+                    SELECT c.description
+                    FROM game_category gc JOIN category c ON gc.category_id = c.id
+                    WHERE gc.app_id = 8700001
+                    ORDER BY c.id
+                "#,
+                &[]
+            )
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| r.get(0))
+            .collect()
+    };
+    let app_type: Option<String> = {
+        check
+            .query_one("SELECT app_type FROM game_details WHERE app_id = 8700001", &[])
+            .await
+            .unwrap()
+            .get(0)
+    };
+
+    assert_eq!(
+        companies,
+        vec![
+            ("developer".to_string(), "First Studio".to_string()),
+            ("publisher".to_string(), "Sequel Publishing".to_string()),
+        ]
+    );
+    assert_eq!(platforms, vec!["linux".to_string(), "windows".to_string()]);
+    assert_eq!(genres, vec!["Simulation".to_string()]);
+    assert_eq!(categories, vec!["Single-player".to_string(), "Remote Play Together".to_string()]);
+    assert_eq!(app_type.as_deref(), Some("game"));
+
+    for table in ["game_company", "game_platform", "game_genre", "game_category", "game_details"] {
+        check.execute(&format!("DELETE FROM {} WHERE app_id = 8700001", table), &[]).await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_release_window() {
+    let mut db_client = db::connect(CONN_STR).await;
+    db::migrate(&mut db_client).await;
+
+    let repo = Repo::new(db_client);
+    let check = db::connect(CONN_STR).await;
+
+    let window_q = r#"
+        SELECT release_earliest, release_latest, release_precision, release_estimate
+        FROM game_details
+        WHERE app_id = 8700002
+    "#;
+
+    let quarter = ReleaseWindow {
+        earliest: Utc.with_ymd_and_hms(2026, 4, 1, 0, 0, 0).single(),
+        latest: Utc.with_ymd_and_hms(2026, 7, 1, 0, 0, 0).single(),
+        precision: ReleasePrecision::Quarter,
+    };
+    let details = GameDetails {
+        id: GameId { app_id: 8700002 },
+        release_date: Some("Q2 2026".to_string()),
+        release_estimate: quarter.estimate(),
+        release_window: Some(quarter),
+        ..details_fixture(&[], vec![])
+    };
+    repo.insert_game_details(std::slice::from_ref(&details)).await.unwrap();
+
+    // Details without a release date at all leave the last known window alone
+    let undated = GameDetails { release_date: None, release_estimate: None, release_window: None, ..details };
+    repo.insert_game_details(&[undated]).await.unwrap();
+
+    let row = check.query_one(window_q, &[]).await.unwrap();
+    let earliest: Option<NaiveDateTime> = row.get(0);
+    let latest: Option<NaiveDateTime> = row.get(1);
+    let precision: Option<String> = row.get(2);
+    let estimate: Option<NaiveDateTime> = row.get(3);
+
+    assert_eq!(earliest.map(|t| t.and_utc()), Utc.with_ymd_and_hms(2026, 4, 1, 0, 0, 0).single());
+    assert_eq!(latest.map(|t| t.and_utc()), Utc.with_ymd_and_hms(2026, 7, 1, 0, 0, 0).single());
+    assert_eq!(precision.as_deref(), Some("quarter"));
+    assert_eq!(estimate, latest);
+
+    check.execute("DELETE FROM game_details WHERE app_id = 8700002", &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_release_date_texts() {
+    let mut db_client = db::connect(CONN_STR).await;
+    db::migrate(&mut db_client).await;
+
+    let repo = Repo::new(db_client);
+    repo.begin_transaction().await.unwrap();
+
+    let details = |app_id: u32| GameDetails {
+        id: GameId { app_id },
+        release_date: Some("Sometime after the sequel".to_string()),
+        release_window: Some(ReleaseWindow::unknown()),
+        ..details_fixture(&[], vec![])
+    };
+    repo.insert_game_details(&[details(8700004), details(8700003)]).await.unwrap();
+
+    let texts = repo.get_release_date_texts().await.unwrap();
+    repo.rollback_transaction().await.unwrap();
+
+    let expected = ReleaseDateText {
+        text: "Sometime after the sequel".to_string(),
+        game_count: 2,
+        example: GameId { app_id: 8700003 },
+        example_name: None,
+    };
+    assert!(texts.contains(&expected), "should group games by release date: {:?}", texts);
+}
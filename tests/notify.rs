@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+
+use chrono::{TimeDelta, TimeZone, Utc};
+use serde_json::Value;
+use wiremock::{MockServer, Mock, ResponseTemplate};
+use wiremock::matchers::{header, method, path};
+
+use rusteam::config;
+use rusteam::db;
+use rusteam::db::repo::{Repo, SteamGamesHandling, SyncEventHandling};
+use rusteam::db::sync::SyncEvent;
+use rusteam::models::game::{GameId, ReleaseChange};
+use rusteam::notify::{self, EventFilter, Notification, Notifier, NotifyError};
+use rusteam::notify::discord::DiscordNotifier;
+use rusteam::notify::slack::SlackNotifier;
+use rusteam::notify::webhook::WebhookNotifier;
+use rusteam::steam::release_date::parse_release_window;
+
+// Games used only by this test, so other events can be told apart
+const RELEASED_GAME: u32 = 8100001;
+const DELAYED_GAME: u32 = 8100002;
+
+async fn repo() -> Repo {
+    let conf = config::read(Some(&"test/test-config.toml".to_string()));
+    let mut db_client = db::connect(&conf.db.connection_string()).await;
+    db::migrate(&mut db_client).await;
+    Repo::new(db_client)
+}
+
+/// Events received by a mock webhook which concern the games used by this test
+fn received_test_events(server_requests: &[wiremock::Request]) -> Vec<Value> {
+    server_requests
+        .iter()
+        .flat_map(|r| {
+            let body: Value = serde_json::from_slice(&r.body).unwrap();
+            body["events"].as_array().unwrap().clone()
+        })
+        .filter(|e| {
+            let app_id = e["game"]["app_id"].as_u64().unwrap() as u32;
+            app_id == RELEASED_GAME || app_id == DELAYED_GAME
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_webhook_notifier() {
+    let repo = repo().await;
+
+    repo.insert_steam_games(HashMap::from([(RELEASED_GAME, "Paint Drying Tycoon 3")])).await.unwrap();
+    repo
+        .insert_sync_events(
+            None,
+            &[
+                SyncEvent::Released { game: GameId { app_id: RELEASED_GAME } },
+                SyncEvent::ReleaseDateUpdated {
+                    game: GameId { app_id: DELAYED_GAME },
+                    prev_text: "Q1 2025".to_string(),
+                    prev_date: None,
+                    new_text: "2026".to_string(),
+                    new_date: None,
+                    change: ReleaseChange::Postponed,
+                },
+            ]
+        )
+        .await
+        .unwrap();
+
+    let mock_webhook = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/hook"))
+        .and(header("Authorization", "Bearer TOKEN"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&mock_webhook)
+        .await;
+
+    let mock_broken_webhook = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(2)
+        .mount(&mock_broken_webhook)
+        .await;
+
+    let notifiers: Vec<Box<dyn Notifier>> = vec![
+        Box::new(
+            WebhookNotifier::new("test-webhook", &format!("{}/hook", &mock_webhook.uri()))
+                .with_headers(HashMap::from([("Authorization".to_string(), "Bearer TOKEN".to_string())]))
+        ),
+        Box::new(WebhookNotifier::new("test-broken-webhook", &mock_broken_webhook.uri())),
+    ];
+
+    // Dispatching twice should only deliver each event once, but keep retrying failures
+    notify::dispatch(&repo, &notifiers, TimeDelta::hours(1)).await;
+    notify::dispatch(&repo, &notifiers, TimeDelta::hours(1)).await;
+
+    let received = received_test_events(&mock_webhook.received_requests().await.unwrap());
+
+    assert_eq!(received.len(), 2);
+    assert_eq!(received[0]["type"], "released");
+    assert_eq!(received[0]["game_name"], "Paint Drying Tycoon 3");
+    assert_eq!(received[1]["type"], "release_date_updated");
+    assert_eq!(received[1]["game_name"], DELAYED_GAME.to_string());
+    assert_eq!(received[1]["prev_text"], "Q1 2025");
+    assert_eq!(received[1]["new_text"], "2026");
+    assert_eq!(received[1]["change"], "postponed");
+
+    let since = Utc::now() - TimeDelta::hours(1);
+    let undelivered = repo.get_undelivered_events("test-broken-webhook", &since).await.unwrap();
+    assert!(undelivered.len() >= 2);
+}
+
+fn notifications_fixture() -> Vec<Notification> {
+    let recorded = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+
+    vec![
+        Notification {
+            event_id: 1,
+            game_name: "Paint Drying Tycoon 3".to_string(),
+            recorded,
+            event: SyncEvent::Released { game: GameId { app_id: 654321 } },
+        },
+        Notification {
+            event_id: 2,
+            game_name: "Unearthed Myths 7".to_string(),
+            recorded,
+            event: SyncEvent::ReleaseDateUpdated {
+                game: GameId { app_id: 666666 },
+                prev_text: "Q1 2025".to_string(),
+                prev_date: Some(Utc.with_ymd_and_hms(2025, 3, 31, 0, 0, 0).unwrap()),
+                new_text: "2026".to_string(),
+                new_date: Some(Utc.with_ymd_and_hms(2026, 12, 31, 0, 0, 0).unwrap()),
+                change: ReleaseChange::Postponed,
+            },
+        },
+        Notification {
+            event_id: 3,
+            game_name: "Game Buying Simulator 2024".to_string(),
+            recorded,
+            event: SyncEvent::OnSale { game: GameId { app_id: 666 }, price: 999, discount_percent: 50 },
+        },
+    ]
+}
+
+async fn sent_body(server: &MockServer) -> Value {
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 1);
+    serde_json::from_slice(&requests[0].body).unwrap()
+}
+
+#[tokio::test]
+async fn test_discord_notifier() {
+    let mock_discord = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/webhooks/123/abc"))
+        .respond_with(ResponseTemplate::new(204))
+        .mount(&mock_discord)
+        .await;
+
+    let discord = DiscordNotifier::new("discord", &format!("{}/api/webhooks/123/abc", &mock_discord.uri()));
+    discord.notify(&notifications_fixture()).await.unwrap();
+
+    let body = sent_body(&mock_discord).await;
+    let embeds = body["embeds"].as_array().unwrap();
+
+    assert_eq!(embeds.len(), 3);
+    assert_eq!(embeds[0]["title"], "🚀 Paint Drying Tycoon 3 is out now");
+    assert_eq!(embeds[0]["url"], "https://store.steampowered.com/app/654321");
+    assert_eq!(
+        embeds[0]["image"]["url"],
+        "https://cdn.akamai.steamstatic.com/steam/apps/654321/header.jpg"
+    );
+    assert_eq!(embeds[1]["title"], "🐌 Delayed: Unearthed Myths 7");
+    assert_eq!(embeds[1]["fields"][0]["value"], "Q1 2025");
+    assert_eq!(embeds[1]["fields"][1]["value"], "2026");
+    assert_eq!(embeds[2]["description"], "💸 Game Buying Simulator 2024 is on sale: 50% off, now £9.99");
+}
+
+#[tokio::test]
+async fn test_discord_notifier_partial_delivery() {
+    let mock_discord = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(204))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&mock_discord)
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(429))
+        .mount(&mock_discord)
+        .await;
+
+    // Enough for two messages, the second of which fails
+    let notifications: Vec<Notification> = {
+        (1..=12)
+            .map(|event_id| Notification { event_id, ..notifications_fixture()[0].clone() })
+            .collect()
+    };
+
+    let discord = DiscordNotifier::new("discord", &mock_discord.uri());
+    let res = discord.notify(&notifications).await;
+
+    match res {
+        Err(NotifyError::Partial { delivered, .. }) => assert_eq!(delivered, (1..=10).collect::<Vec<i64>>()),
+        other => panic!("should report which notifications were delivered: {:?}", other),
+    }
+
+    // Nothing delivered at all is a plain failure
+    let res = discord.notify(&notifications[10..]).await;
+    assert!(matches!(res, Err(NotifyError::Status(..))));
+}
+
+#[tokio::test]
+async fn test_slack_notifier() {
+    let mock_slack = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/services/T000/B000/XXX"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+        .mount(&mock_slack)
+        .await;
+
+    let slack = SlackNotifier::new("slack", &format!("{}/services/T000/B000/XXX", &mock_slack.uri()));
+    slack.notify(&notifications_fixture()[..2]).await.unwrap();
+
+    let body = sent_body(&mock_slack).await;
+    let blocks = body["blocks"].as_array().unwrap();
+
+    assert_eq!(blocks.len(), 4);
+    assert_eq!(
+        blocks[0]["text"]["text"],
+        "🚀 *<https://store.steampowered.com/app/654321|Paint Drying Tycoon 3>* is out now!"
+    );
+    assert_eq!(
+        blocks[2]["text"]["text"],
+        "🐌 Delayed: *<https://store.steampowered.com/app/666666|Unearthed Myths 7>*\n~Q1 2025~ → *2026*"
+    );
+    assert_eq!(
+        blocks[2]["accessory"]["image_url"],
+        "https://cdn.akamai.steamstatic.com/steam/apps/666666/header.jpg"
+    );
+    assert!(body["text"].as_str().unwrap().contains("Unearthed Myths 7"));
+}
+
+#[test]
+fn test_event_filter() {
+    let notifications = notifications_fixture();
+    let releases_only = EventFilter::new(&Some(vec!["released".to_string(), "release_date_updated".to_string()]));
+    let everything = EventFilter::new(&None);
+
+    let accepted: Vec<i64> = {
+        notifications.iter().filter(|n| releases_only.accepts(&n.event)).map(|n| n.event_id).collect()
+    };
+
+    assert_eq!(accepted, vec![1, 2]);
+    assert!(notifications.iter().all(|n| everything.accepts(&n.event)));
+}
+
+#[test]
+fn test_release_change() {
+    let classify = |prev: &str, new: &str| {
+        ReleaseChange::classify(parse_release_window(prev).as_ref(), parse_release_window(new).as_ref())
+    };
+
+    assert_eq!(classify("Q1 2025", "2026"), ReleaseChange::Postponed);
+    assert_eq!(classify("Mar 2025", "Q2 2025"), ReleaseChange::Postponed);
+    assert_eq!(classify("2026", "Q4 2025"), ReleaseChange::BroughtForward);
+    assert_eq!(classify("2026", "Q2 2026"), ReleaseChange::Narrowed);
+    assert_eq!(classify("Q2 2026", "18 May, 2026"), ReleaseChange::Narrowed);
+    assert_eq!(classify("Coming soon", "18 Sep, 2025"), ReleaseChange::Narrowed);
+    assert_eq!(classify("Q2 2026", "2026"), ReleaseChange::Widened);
+    assert_eq!(classify("18 Sep, 2025", "To be announced"), ReleaseChange::Tba);
+    assert_eq!(classify("Coming soon", "To be announced"), ReleaseChange::Changed);
+    assert_eq!(classify("5 Jan, 2026", "Jan 5, 2026"), ReleaseChange::Changed);
+    // Text the parser doesn't understand isn't taken to mean the date was dropped
+    assert_eq!(classify("18 Sep, 2025", "Out when the stars align"), ReleaseChange::Changed);
+    assert_eq!(classify("Out when the stars align", "To be announced"), ReleaseChange::Changed);
+}
@@ -0,0 +1,82 @@
+mod utils;
+
+use serde_json::json;
+use tokio;
+use wiremock::{MockServer, Mock, ResponseTemplate};
+use wiremock::matchers::{body_partial_json, method, path};
+
+use rusteam::notion::{NotionGamesRepo, NotionHandling};
+
+const DATABASE_ID: &str = "5d794de0222449d386f93540db13d884";
+
+async fn mount_notes_page(server: &MockServer, page: u32, cursor: Option<&str>) {
+    let response = utils::fixture(&format!("notion/notes-page-{}.json", page));
+    let mock = Mock::given(method("POST"))
+        .and(path(format!("/v1/databases/{}/query", DATABASE_ID)));
+
+    let mock = match cursor {
+        Some(c) => mock.and(body_partial_json(json!({ "start_cursor": c }))),
+        None => mock,
+    };
+
+    let mock = mock
+        .respond_with(
+            ResponseTemplate::new(200).set_body_raw(response.as_bytes(), "application/json")
+        )
+        .expect(1);
+
+    // The first page is requested without a cursor, so let it act as a fallback for the others
+    let mock = if cursor.is_none() { mock.with_priority(10) } else { mock };
+
+    mock
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_get_notes_follows_cursor() {
+    let mock_notion = MockServer::start().await;
+
+    mount_notes_page(&mock_notion, 1, None).await;
+    mount_notes_page(&mock_notion, 2, Some("cursor-2")).await;
+    mount_notes_page(&mock_notion, 3, Some("cursor-3")).await;
+
+    let notion = NotionGamesRepo::new(
+        "NOTION API KEY",
+        DATABASE_ID,
+        &format!("http://{}", &mock_notion.address())
+    );
+
+    let notes = notion.get_notes().await.unwrap();
+
+    let expected = vec![
+        "Game Buying Simulator 2024",
+        "Final Fantasy MMLXVII",
+        "Paint Drying Tycoon 2",
+        "Unearthed Myths 7: The Unearthening",
+        "Game Buying Simulator 2025",
+    ];
+    let actual: Vec<&str> = notes.iter().filter_map(|n| n.name.as_deref()).collect();
+
+    assert_eq!(actual, expected);
+    assert_eq!(notes[1].app_id, Some("1337".to_string()));
+    assert_eq!(notes[2].app_id, None);
+}
+
+#[tokio::test]
+async fn test_get_notes_single_page() {
+    let mock_notion = MockServer::start().await;
+
+    mount_notes_page(&mock_notion, 3, None).await;
+
+    let notion = NotionGamesRepo::new(
+        "NOTION API KEY",
+        DATABASE_ID,
+        &format!("http://{}", &mock_notion.address())
+    );
+
+    let notes = notion.get_notes().await.unwrap();
+
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].name, Some("Game Buying Simulator 2025".to_string()));
+}
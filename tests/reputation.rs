@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+
+use rusteam::db;
+use rusteam::db::repo::{
+    GameDetailsHandling, ReleaseUpdateHandling, Repo, SteamGamesHandling, WishlistHandling,
+};
+use rusteam::models::game::{
+    GameDetails, GameId, ReleaseChange, StudioReleaseUpdate, UpcomingGame, WishlistedGame,
+};
+use rusteam::reputation::{adjusted_estimate, rank_studios, StudioReputation};
+
+const CONN_STR: &str = "host=localhost port=15432 user=tests password=test_admin dbname=rusteam_test";
+
+fn date(y: i32, m: u32, d: u32) -> Option<DateTime<Utc>> {
+    Utc.with_ymd_and_hms(y, m, d, 0, 0, 0).single()
+}
+
+fn update(
+    studio: &str,
+    app_id: u32,
+    change: ReleaseChange,
+    prev: Option<DateTime<Utc>>,
+    new: Option<DateTime<Utc>>
+) -> StudioReleaseUpdate {
+    StudioReleaseUpdate {
+        studio: studio.to_string(),
+        role: "developer".to_string(),
+        game: GameId { app_id },
+        change: Some(change),
+        prev_estimate: prev,
+        new_estimate: new,
+    }
+}
+
+/// A game whose release date has never changed
+fn steady(studio: &str, app_id: u32) -> StudioReleaseUpdate {
+    StudioReleaseUpdate { change: None, ..update(studio, app_id, ReleaseChange::Changed, None, None) }
+}
+
+fn details_fixture(app_id: u32, developers: &[&str], publishers: &[&str]) -> GameDetails {
+    GameDetails {
+        id: GameId { app_id },
+        name: None,
+        app_type: Some("game".to_string()),
+        is_free: Some(false),
+        description: None,
+        header_image: None,
+        controller_support: None,
+        coop: false,
+        local_coop: false,
+        metacritic_percent: None,
+        is_released: false,
+        release_date: Some("2027".to_string()),
+        release_estimate: date(2028, 1, 1),
+        release_window: None,
+        categories: vec![],
+        genres: vec![],
+        developers: developers.iter().map(|d| d.to_string()).collect(),
+        publishers: publishers.iter().map(|p| p.to_string()).collect(),
+        platforms: vec![],
+        recorded: Utc::now(),
+    }
+}
+
+#[test]
+fn test_rank_studios() {
+    let updates = vec![
+        // Delayed twice on one game, by 90 then 30 days, and brought forward on another
+        update("Slow Studio", 1, ReleaseChange::Postponed, date(2025, 1, 1), date(2025, 4, 1)),
+        update("Slow Studio", 1, ReleaseChange::Postponed, date(2025, 4, 1), date(2025, 5, 1)),
+        update("Slow Studio", 2, ReleaseChange::BroughtForward, date(2025, 6, 1), date(2025, 5, 1)),
+        // Back to TBA, which counts as a delay without a measurable slip
+        update("Vague Studio", 3, ReleaseChange::Tba, date(2025, 1, 1), None),
+        update("Punctual Studio", 4, ReleaseChange::Narrowed, date(2025, 7, 1), date(2025, 6, 15)),
+        steady("Punctual Studio", 5),
+        // Delayed once by 40 days, across four games
+        update("Mostly Reliable Studio", 6, ReleaseChange::Postponed, date(2025, 1, 1), date(2025, 2, 10)),
+        steady("Mostly Reliable Studio", 7),
+        steady("Mostly Reliable Studio", 8),
+        steady("Mostly Reliable Studio", 9),
+    ];
+
+    let actual = rank_studios(&updates);
+    let studios: Vec<&str> = actual.iter().map(|r| r.studio.as_str()).collect();
+
+    assert_eq!(studios, vec!["Slow Studio", "Mostly Reliable Studio", "Vague Studio", "Punctual Studio"]);
+
+    let slow = &actual[0];
+    assert_eq!((slow.games, slow.games_delayed, slow.delays), (2, 1, 2));
+    assert_eq!(slow.total_slip, TimeDelta::days(120));
+    assert_eq!(slow.average_slip(), TimeDelta::days(60));
+    assert_eq!(slow.expected_slip(), TimeDelta::days(60));
+    assert_eq!(slow.delay_rate(), 0.5);
+
+    // Games which were never delayed count towards how often a studio delays
+    let reliable = &actual[1];
+    assert_eq!((reliable.games, reliable.games_delayed, reliable.delays), (4, 1, 1));
+    assert_eq!(reliable.delay_rate(), 0.25);
+    assert_eq!(reliable.average_slip(), TimeDelta::days(40));
+    assert_eq!(reliable.expected_slip(), TimeDelta::days(10));
+
+    let vague = &actual[2];
+    assert_eq!((vague.games, vague.games_delayed, vague.delays), (1, 1, 1));
+    assert_eq!(vague.total_slip, TimeDelta::zero());
+
+    let punctual = &actual[3];
+    assert_eq!((punctual.games, punctual.games_delayed, punctual.delays), (2, 0, 0));
+    assert_eq!(punctual.delay_rate(), 0.0);
+
+    // Estimates are pushed back by the worst of the studios behind the game
+    let studios: Vec<&StudioReputation> = actual.iter().collect();
+    assert_eq!(adjusted_estimate(date(2026, 1, 1), &studios), date(2026, 3, 2));
+    assert_eq!(adjusted_estimate(date(2026, 1, 1), &[]), date(2026, 1, 1));
+    assert_eq!(adjusted_estimate(None, &studios), None);
+}
+
+#[tokio::test]
+async fn test_studio_release_history() {
+    let mut db_client = db::connect(CONN_STR).await;
+    db::migrate(&mut db_client).await;
+
+    let repo = Repo::new(db_client);
+    repo.begin_transaction().await.unwrap();
+
+    let delayed = GameId { app_id: 8800001 };
+    let on_time = GameId { app_id: 8800002 };
+    repo
+        .insert_steam_games(HashMap::from([
+            (8800001, "Perpetually Next Year"),
+            (8800002, "Right On Time"),
+            (8800003, "Never Moved"),
+        ]))
+        .await
+        .unwrap();
+
+    let details = vec![
+        details_fixture(delayed.app_id, &["Reputation Dev"], &["Reputation Pub"]),
+        details_fixture(on_time.app_id, &["Reputation Dev"], &["Reputation Dev"]),
+        details_fixture(8800003, &["Reputation Pub"], &[]),
+    ];
+    repo.insert_game_details(&details).await.unwrap();
+    repo.update_game_metadata(&details).await.unwrap();
+
+    repo.insert_release_update(&delayed, "2026", &date(2027, 1, 1), "2027", &date(2028, 1, 1), ReleaseChange::Postponed)
+        .await
+        .unwrap();
+    repo.insert_release_update(&on_time, "2027", &date(2028, 1, 1), "Q2 2027", &date(2027, 7, 1), ReleaseChange::Narrowed)
+        .await
+        .unwrap();
+
+    // Keep the rest of the wishlist as it is, so only this game is added
+    let mut wishlist = repo.get_wishlisted_ids().await.unwrap();
+    wishlist.insert(delayed.clone());
+    let items: Vec<WishlistedGame> = {
+        wishlist.into_iter().map(|id| WishlistedGame { id, wishlisted: Utc::now(), deleted: None }).collect()
+    };
+    repo.update_wishlist(&items).await.unwrap();
+
+    let updates = repo.get_studio_release_updates().await.unwrap();
+    let upcoming = repo.get_upcoming_wishlist().await.unwrap();
+    repo.rollback_transaction().await.unwrap();
+
+    let ours: Vec<(&str, &str, u32, Option<ReleaseChange>)> = {
+        updates
+            .iter()
+            .filter(|u| u.studio.starts_with("Reputation "))
+            .map(|u| (u.studio.as_str(), u.role.as_str(), u.game.app_id, u.change))
+            .collect()
+    };
+    assert_eq!(
+        ours,
+        vec![
+            ("Reputation Dev", "developer", 8800001, Some(ReleaseChange::Postponed)),
+            ("Reputation Dev", "developer", 8800002, Some(ReleaseChange::Narrowed)),
+            ("Reputation Dev", "publisher", 8800002, Some(ReleaseChange::Narrowed)),
+            // Games whose release date never changed are included, to count as on time
+            ("Reputation Pub", "developer", 8800003, None),
+            ("Reputation Pub", "publisher", 8800001, Some(ReleaseChange::Postponed)),
+        ]
+    );
+
+    let expected = UpcomingGame {
+        id: delayed,
+        name: Some("Perpetually Next Year".to_string()),
+        release_date: Some("2027".to_string()),
+        release_estimate: date(2028, 1, 1),
+        developers: vec!["Reputation Dev".to_string()],
+        publishers: vec!["Reputation Pub".to_string()],
+        delays: 1,
+    };
+    let ours: Vec<&UpcomingGame> = upcoming.iter().filter(|g| g.id.app_id == 8800001).collect();
+    assert_eq!(ours, vec![&expected]);
+}
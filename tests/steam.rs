@@ -1,18 +1,22 @@
 mod utils;
 
+use std::fs;
 use std::time::Duration;
 
 use chrono::{DateTime, TimeZone, Utc};
 use tokio;
 use wiremock::{MockServer, Mock, ResponseTemplate};
-use wiremock::matchers::{method, path};
+use wiremock::matchers::{method, path, query_param};
 
-use rusteam::models::game::{GameId, SteamPlaytime, WishlistedGame};
-use rusteam::models::steam::SteamAppIdPair;
+use rusteam::models::game::{AppType, GameId, GamePrice, SteamApp, SteamPlaytime, WishlistedGame};
 use rusteam::steam::{
+    DetailsFailure,
     SteamClient,
+    StoreLimits,
+    SteamAppDetailsHandling,
     SteamAppsServiceHandling,
     SteamPlayerServiceHandling,
+    SteamPriceHandling,
     SteamWishlistHandling
 };
 
@@ -87,15 +91,30 @@ async fn test_get_played_games() {
 }
 
 #[tokio::test]
-async fn test_get_all_games() {
+async fn test_get_app_list() {
     let mock_steam = MockServer::start().await;
-    let response = utils::fixture("steam-games/steam-games-1.json");
+    let page_1 = utils::fixture("steam-games/steam-games-1.json");
+    let page_2 = utils::fixture("steam-games/steam-games-2.json");
 
     Mock::given(method("GET"))
-        .and(path("/ISteamApps/GetAppList/v2/"))
+        .and(path("/IStoreService/GetAppList/v1/"))
+        .and(query_param("include_games", "true"))
+        .and(query_param("include_dlc", "false"))
+        .and(query_param("if_modified_since", "1704067200"))
         .respond_with(
-            ResponseTemplate::new(200).set_body_raw(response.as_bytes(), "application/json")
+            ResponseTemplate::new(200).set_body_raw(page_1.as_bytes(), "application/json")
+        )
+        .mount(&mock_steam)
+        .await;
+
+    // The second page follows on from the last app on the first
+    Mock::given(method("GET"))
+        .and(path("/IStoreService/GetAppList/v1/"))
+        .and(query_param("last_appid", "1337"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_raw(page_2.as_bytes(), "application/json")
         )
+        .with_priority(1)
         .mount(&mock_steam)
         .await;
 
@@ -105,13 +124,19 @@ async fn test_get_all_games() {
         &format!("http://{}", &mock_steam.address())
     );
 
-    // TODO: DRY, reuse the identical fixture list from unit tests
+    let app = |app_id: u32, name: &str, last_modified: DateTime<Utc>| SteamApp {
+        id: GameId { app_id },
+        name: name.to_string(),
+        app_type: AppType::Game,
+        last_modified,
+    };
     let expected = vec![
-        SteamAppIdPair { appid: 666, name: "Game Buying Simulator 2024".to_string() },
-        SteamAppIdPair { appid: 1337, name: "Final Fantasy MMLXVII".to_string() },
-        SteamAppIdPair { appid: 654321, name: "Paint Drying Tycoon 2".to_string() },
+        app(666, "Game Buying Simulator 2024", Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap()),
+        app(1337, "Final Fantasy MMLXVII", Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap()),
+        app(654321, "Paint Drying Tycoon 2", Utc.with_ymd_and_hms(2024, 4, 2, 0, 0, 0).unwrap()),
     ];
-    let actual = steam_client.get_all_games().unwrap();
+    let since = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let actual = steam_client.get_app_list(AppType::Game, Some(since)).unwrap();
 
     assert_eq!(actual, expected);
 }
@@ -157,3 +182,224 @@ async fn test_get_wishlist() {
 
     assert_eq!(actual, expected);
 }
+
+#[tokio::test]
+async fn test_get_prices() {
+    let mock_steam = MockServer::start().await;
+    let response = utils::fixture("prices/prices-1.json");
+
+    Mock::given(method("GET"))
+        .and(path("/api/appdetails"))
+        .and(query_param("filters", "price_overview"))
+        .and(query_param("appids", "666,1337,666666,654321"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_raw(response.as_bytes(), "application/json")
+        )
+        .expect(1)
+        .mount(&mock_steam)
+        .await;
+
+    let steam_client = SteamClient::new(
+        "STEAM API KEY",
+        &format!("http://{}", &mock_steam.address()),
+        &format!("http://{}", &mock_steam.address())
+    );
+
+    let ids = vec![
+        GameId { app_id: 666 },
+        GameId { app_id: 1337 },
+        GameId { app_id: 666666 },
+        GameId { app_id: 654321 },
+    ];
+    let (mut actual, failures) = steam_client.get_prices(&ids).await.unwrap();
+    actual.sort_by_key(|p| p.id.app_id);
+
+    // Games with no price or a failed lookup are left out
+    let recorded = actual[0].recorded;
+    let expected = vec![
+        GamePrice { id: GameId { app_id: 666 }, price: 999, discount_percent: 50, recorded },
+        GamePrice { id: GameId { app_id: 1337 }, price: 5999, discount_percent: 0, recorded },
+    ];
+
+    assert_eq!(actual, expected);
+    assert_eq!(failures, vec![]);
+}
+
+#[tokio::test]
+async fn test_get_prices_skips_failed_batch() {
+    let mock_steam = MockServer::start().await;
+    let response = utils::fixture("prices/prices-1.json");
+
+    // The first batch keeps failing, while the second is fine
+    Mock::given(method("GET"))
+        .and(path("/api/appdetails"))
+        .and(query_param("filters", "price_overview"))
+        .respond_with(ResponseTemplate::new(503))
+        .with_priority(10)
+        .mount(&mock_steam)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/appdetails"))
+        .and(query_param("filters", "price_overview"))
+        .and(query_param("appids", "666,1337,666666,654321"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_raw(response.as_bytes(), "application/json")
+        )
+        .expect(1)
+        .mount(&mock_steam)
+        .await;
+
+    let steam_client = SteamClient::new(
+        "STEAM API KEY",
+        &format!("http://{}", &mock_steam.address()),
+        &format!("http://{}", &mock_steam.address())
+    ).with_store_limits(fast_store_limits(1));
+
+    let failing: Vec<GameId> = (9000001..=9000100).map(|app_id| GameId { app_id }).collect();
+    let ids: Vec<GameId> = {
+        failing
+            .iter()
+            .copied()
+            .chain([666, 1337, 666666, 654321].map(|app_id| GameId { app_id }))
+            .collect()
+    };
+    let (prices, failures) = steam_client.get_prices(&ids).await.unwrap();
+
+    let mut priced: Vec<u32> = prices.iter().map(|p| p.id.app_id).collect();
+    priced.sort();
+    let failed: Vec<GameId> = failures.iter().map(|(id, _)| *id).collect();
+
+    assert_eq!(priced, vec![666, 1337]);
+    assert_eq!(failed, failing);
+    assert!(failures.iter().all(|(_, f)| matches!(f, DetailsFailure::Transient(_))));
+}
+
+// Limits allowing tests to run quickly while still exercising retries
+fn fast_store_limits(max_retries: u32) -> StoreLimits {
+    StoreLimits {
+        requests_per_window: 100,
+        window: Duration::from_secs(1),
+        concurrency: 4,
+        max_retries,
+        backoff: Duration::from_millis(10),
+    }
+}
+
+// The Stardew Valley appdetails fixture, relabelled with the given app ID
+fn app_details_response(app_id: u32) -> String {
+    fs::read_to_string("resources/test/steam/app-details-response-1.json")
+        .unwrap()
+        .replace("413150", &app_id.to_string())
+}
+
+async fn mount_app_details(server: &MockServer, app_id: u32, template: ResponseTemplate) {
+    Mock::given(method("GET"))
+        .and(path("/api/appdetails"))
+        .and(query_param("appids", app_id.to_string()))
+        .respond_with(template)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_get_game_details() {
+    let mock_steam = MockServer::start().await;
+
+    mount_app_details(
+        &mock_steam,
+        413150,
+        ResponseTemplate::new(200).set_body_raw(app_details_response(413150), "application/json")
+    ).await;
+
+    // Throttled once, then succeeds
+    Mock::given(method("GET"))
+        .and(path("/api/appdetails"))
+        .and(query_param("appids", "666"))
+        .respond_with(ResponseTemplate::new(429))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .expect(1)
+        .mount(&mock_steam)
+        .await;
+    mount_app_details(
+        &mock_steam,
+        666,
+        ResponseTemplate::new(200).set_body_raw(app_details_response(666), "application/json")
+    ).await;
+
+    // Not a well-formed definition, so should be reported as a failure
+    mount_app_details(
+        &mock_steam,
+        1337,
+        ResponseTemplate::new(200).set_body_raw(r#"{"1337": {"success": false}}"#, "application/json")
+    ).await;
+
+    let steam_client = SteamClient::new(
+        "STEAM API KEY",
+        &format!("http://{}", &mock_steam.address()),
+        &format!("http://{}", &mock_steam.address())
+    ).with_store_limits(fast_store_limits(3));
+
+    let ids = vec![GameId { app_id: 413150 }, GameId { app_id: 666 }, GameId { app_id: 1337 }];
+    let (mut details, failures) = steam_client.get_game_details(&ids).await.unwrap();
+    details.sort_by_key(|d| d.id.app_id);
+
+    let actual: Vec<(u32, Option<String>)> = {
+        details.into_iter().map(|d| (d.id.app_id, d.release_date)).collect()
+    };
+    let expected = vec![
+        (666, Some("26 Feb, 2016".to_string())),
+        (413150, Some("26 Feb, 2016".to_string())),
+    ];
+
+    assert_eq!(actual, expected);
+    let failed_ids: Vec<GameId> = failures.iter().map(|(id, _)| *id).collect();
+
+    assert_eq!(failed_ids, vec![GameId { app_id: 1337 }]);
+    assert!(matches!(failures[0].1, DetailsFailure::Permanent(_)));
+}
+
+#[tokio::test]
+async fn test_get_game_details_gives_up_retrying() {
+    let mock_steam = MockServer::start().await;
+
+    mount_app_details(
+        &mock_steam,
+        413150,
+        ResponseTemplate::new(200).set_body_raw(app_details_response(413150), "application/json")
+    ).await;
+    Mock::given(method("GET"))
+        .and(path("/api/appdetails"))
+        .and(query_param("appids", "666"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(3)
+        .mount(&mock_steam)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/appdetails"))
+        .and(query_param("appids", "1337"))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(1)
+        .mount(&mock_steam)
+        .await;
+
+    let steam_client = SteamClient::new(
+        "STEAM API KEY",
+        &format!("http://{}", &mock_steam.address()),
+        &format!("http://{}", &mock_steam.address())
+    ).with_store_limits(fast_store_limits(2));
+
+    // The batch should still succeed, with the games that couldn't be fetched reported
+    let ids = vec![GameId { app_id: 413150 }, GameId { app_id: 666 }, GameId { app_id: 1337 }];
+    let (details, mut failures) = steam_client.get_game_details(&ids).await.unwrap();
+    failures.sort_by_key(|(id, _)| id.app_id);
+
+    assert_eq!(details.len(), 1);
+    assert_eq!(details[0].id, GameId { app_id: 413150 });
+
+    assert_eq!(failures.len(), 2);
+    assert_eq!(failures[0].0, GameId { app_id: 666 });
+    assert!(matches!(failures[0].1, DetailsFailure::Transient(_)));
+    assert_eq!(failures[1].0, GameId { app_id: 1337 });
+    assert!(matches!(failures[1].1, DetailsFailure::Permanent(_)));
+}
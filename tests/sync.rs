@@ -1,20 +1,26 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::time::Duration;
 
 use async_trait::async_trait;
-use chrono::{TimeZone, Utc};
+use chrono::{DateTime, TimeZone, Utc};
 use mockall::*;
 use mockall::predicate::*;
 use tokio;
 
 use rusteam::db;
-use rusteam::db::repo::Repo;
-use rusteam::db::sync::Sync;
-use rusteam::notion::{NotionHandling, Result as NotionResult};
+use rusteam::db::repo::{
+    AppIdCandidateHandling, GameDetailsHandling, OwnedGamesHandling, ReleaseUpdateHandling, Repo, SteamGamesHandling,
+    SyncEventHandling, SyncRunHandling
+};
+use rusteam::db::sync::{Sync, SyncEvent};
+use rusteam::notion::{DryRunNotion, NotionHandling, Result as NotionResult};
 use rusteam::steam::{Result as SteamResult, *};
-use rusteam::models::game::{GameDetails, GameId, GameState, SteamPlaytime, WishlistedGame};
+use rusteam::models::game::{
+    AppType, Category, GameDetails, GameId, GamePrice, GameState, Genre, Platform, SteamApp, SteamPlaytime,
+    WishlistedGame
+};
 use rusteam::models::notion::GameNote;
-use rusteam::models::steam::SteamAppIdPair;
+use rusteam::models::sync::{SyncPhase, SyncStatus};
 
 mock! {
     pub SteamClient {}
@@ -24,10 +30,15 @@ mock! {
         fn get_played_games(&self, account_id: &str) -> SteamResult<Vec<SteamPlaytime>>;
     }
     impl SteamAppsServiceHandling for SteamClient {
-        fn get_all_games(&self) -> SteamResult<Vec<SteamAppIdPair>>;
+        fn get_app_list(&self, app_type: AppType, since: Option<DateTime<Utc>>) -> SteamResult<Vec<SteamApp>>;
     }
+    #[async_trait]
     impl SteamAppDetailsHandling for SteamClient {
-        fn get_game_details(&self, ids: &[GameId]) -> SteamResult<(Vec<GameDetails>, Vec<GameId>)>;
+        async fn get_game_details(&self, ids: &[GameId]) -> SteamResult<(Vec<GameDetails>, DetailsFailures)>;
+    }
+    #[async_trait]
+    impl SteamPriceHandling for SteamClient {
+        async fn get_prices(&self, ids: &[GameId]) -> SteamResult<(Vec<GamePrice>, DetailsFailures)>;
     }
     impl SteamWishlistHandling for SteamClient {
         fn get_wishlist(&self, account_id: &str) -> Result<Vec<WishlistedGame>>;
@@ -47,29 +58,45 @@ mock! {
     }
 }
 
-fn steam_app_list_fixture() -> Vec<SteamAppIdPair> {
+fn steam_app_list_fixture() -> Vec<SteamApp> {
+    let app = |app_id: u32, name: &str| SteamApp {
+        id: GameId { app_id },
+        name: name.to_string(),
+        app_type: AppType::Game,
+        last_modified: Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap(),
+    };
+
     vec![
-        SteamAppIdPair { appid: 666, name: "Game Buying Simulator 2024".to_string() },
-        SteamAppIdPair { appid: 1337, name: "Final Fantasy MMLXVII".to_string() },
-        SteamAppIdPair { appid: 654321, name: "Paint Drying Tycoon 2".to_string() },
-        SteamAppIdPair { appid: 666666, name: "Unearthed Myths 7: The Unearthening".to_string() },
+        app(666, "Game Buying Simulator 2024"),
+        app(1337, "Final Fantasy MMLXVII"),
+        app(654321, "Paint Drying Tycoon 2"),
+        app(666666, "Unearthed Myths 7: The Unearthening"),
     ]
 }
 
+/// The fixture apps of the given type, as the app list for that type would give them
+fn steam_app_list_by_type(app_type: AppType) -> Vec<SteamApp> {
+    steam_app_list_fixture().into_iter().filter(|a| a.app_type == app_type).collect()
+}
+
 fn steam_owned_games_fixture() -> Vec<GameId> {
     vec![GameId { app_id: 666 }, GameId { app_id: 1337 }]
 }
 
 /// N.B. the get_game_details signature returns (results, failures) as some game details might
-/// no longer be available in the store
-fn steam_game_details_fixture() -> (Vec<GameDetails>, Vec<GameId>) {
+/// no longer be available in the store, or the store might be having a bad day
+fn steam_game_details_fixture() -> (Vec<GameDetails>, DetailsFailures) {
     let now = Utc::now();
 
     (
         vec![
             GameDetails {
                 id: GameId { app_id: 666 },
+                name: Some("Game Buying Simulator 2024".to_string()),
+                app_type: Some("game".to_string()),
+                is_free: Some(false),
                 description: Some("The thrill of buying games I'll never play!".to_string()),
+                header_image: Some(GameId { app_id: 666 }.header_image_url()),
                 controller_support: Some("partial".to_string()),
                 coop: false,
                 local_coop: false,
@@ -77,11 +104,21 @@ fn steam_game_details_fixture() -> (Vec<GameDetails>, Vec<GameId>) {
                 is_released: true,
                 release_date: Some("1 Jan, 2002".to_string()),
                 release_estimate: None,
+                release_window: None,
+                categories: vec![],
+                genres: vec![Genre { id: 1, description: "Action".to_string() }],
+                developers: vec!["Impulse Labs".to_string()],
+                publishers: vec!["Impulse Labs".to_string()],
+                platforms: vec![Platform::Windows],
                 recorded: now.clone()
             },
             GameDetails {
                 id: GameId { app_id: 1337 },
+                name: Some("Final Fantasy MMLXVII".to_string()),
+                app_type: Some("game".to_string()),
+                is_free: Some(false),
                 description: Some("Now with even better graphics".to_string()),
+                header_image: Some(GameId { app_id: 1337 }.header_image_url()),
                 controller_support: Some("full".to_string()),
                 coop: true,
                 local_coop: true,
@@ -89,11 +126,24 @@ fn steam_game_details_fixture() -> (Vec<GameDetails>, Vec<GameId>) {
                 is_released: false,
                 release_date: Some("Q3 2077".to_string()),
                 release_estimate: None,
+                release_window: None,
+                categories: vec![
+                    Category { id: 9, description: "Co-op".to_string() },
+                    Category { id: 39, description: "Shared/Split Screen Co-op".to_string() },
+                ],
+                genres: vec![Genre { id: 3, description: "RPG".to_string() }],
+                developers: vec!["Square Peg".to_string()],
+                publishers: vec!["Square Peg".to_string()],
+                platforms: vec![Platform::Windows, Platform::Linux],
                 recorded: now.clone()
             },
             GameDetails {
                 id: GameId { app_id: 666666 },
+                name: Some("Unearthed Myths 7: The Unearthening".to_string()),
+                app_type: Some("game".to_string()),
+                is_free: Some(false),
                 description: Some("Unearth some unearthenings".to_string()),
+                header_image: Some(GameId { app_id: 666666 }.header_image_url()),
                 controller_support: Some("full".to_string()),
                 coop: false,
                 local_coop: false,
@@ -101,13 +151,35 @@ fn steam_game_details_fixture() -> (Vec<GameDetails>, Vec<GameId>) {
                 is_released: false,
                 release_date: Some("Coming soon".to_string()),
                 release_estimate: None,
+                release_window: None,
+                categories: vec![],
+                genres: vec![Genre { id: 25, description: "Adventure".to_string() }],
+                developers: vec!["Deep Dig Games".to_string()],
+                publishers: vec!["Myth Publishing".to_string()],
+                platforms: vec![Platform::Windows],
                 recorded: now.clone()
             },
         ],
-        vec![]
+        vec![
+            (
+                GameId { app_id: 654321 },
+                DetailsFailure::Transient("Error communicating with the steam store".to_string())
+            ),
+        ]
     )
 }
 
+fn steam_prices_fixture() -> Vec<GamePrice> {
+    vec![
+        GamePrice {
+            id: GameId { app_id: 666 },
+            price: 999,
+            discount_percent: 50,
+            recorded: Utc::now(),
+        }
+    ]
+}
+
 fn steam_played_games_fixture() -> Vec<SteamPlaytime> {
     vec![
         SteamPlaytime {
@@ -144,7 +216,27 @@ fn notion_game_notes_fixture() -> Vec<GameNote> {
             notes: Some("At least I'll get high off the fumes?".to_string()),
             rating: Some(1),
             created_time: Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap()
-        }
+        },
+        GameNote {
+            id: "000002".to_string(),
+            name: Some("Final Fantasy MMLXVII™".to_string()),
+            app_id: None,
+            state: Some(GameState::Upcoming),
+            tags: vec![],
+            notes: None,
+            rating: None,
+            created_time: Utc.with_ymd_and_hms(2024, 5, 2, 0, 0, 0).unwrap()
+        },
+        GameNote {
+            id: "000003".to_string(),
+            name: Some("Unearthed Myths 7".to_string()),
+            app_id: None,
+            state: Some(GameState::Upcoming),
+            tags: vec![],
+            notes: None,
+            rating: None,
+            created_time: Utc.with_ymd_and_hms(2024, 5, 3, 0, 0, 0).unwrap()
+        },
     ]
 }
 
@@ -159,9 +251,9 @@ async fn test_basic_sync() {
     let mut steam_client = MockSteamClient::new();
 
     steam_client
-        .expect_get_all_games()
-        .times(1)
-        .returning(|| Ok(steam_app_list_fixture()));
+        .expect_get_app_list()
+        .times(AppType::ALL.len())
+        .returning(|t, _| Ok(steam_app_list_by_type(t)));
 
     steam_client
         .expect_get_owned_games()
@@ -192,6 +284,11 @@ async fn test_basic_sync() {
         .times(1)
         .returning(|_| Ok(steam_game_details_fixture()));
 
+    steam_client
+        .expect_get_prices()
+        .times(1)
+        .returning(|_| Ok((steam_prices_fixture(), vec![])));
+
     steam_client
         .expect_get_played_games()
         .with(predicate::eq("STEAMID"))
@@ -216,11 +313,546 @@ async fn test_basic_sync() {
         .times(1)
         .returning(|_, _, _| Ok(()));
 
+    // Should also fill in an app ID found by a confident fuzzy match, keeping the note's name
+    notion_client
+        .expect_set_game_details()
+        .with(
+            predicate::eq("000002"),
+            predicate::eq("1337"),
+            predicate::eq("Final Fantasy MMLXVII™")
+        )
+        .times(1)
+        .returning(|_, _, _| Ok(()));
+
     let mut sync = Sync::new("STEAMID", repo, Box::new(steam_client), Box::new(notion_client));
 
     // Run the sync
-    sync.sync_steam().await.unwrap();
-    sync.sync_notion().await.unwrap();
+    sync.run().await.unwrap();
+
+    // A less certain match should be left for review rather than applied
+    let pending: Vec<(String, GameId)> = {
+        sync.repo
+            .get_pending_candidates()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(_, c)| (c.note_id, c.app_id))
+            .collect()
+    };
+    assert_eq!(pending, vec![("000003".to_string(), GameId { app_id: 666666 })]);
+
+    // Games which couldn't be fetched should be reported at the end of the sync
+    let skipped: Vec<GameId> = sync.skipped_details.iter().map(|(id, _)| *id).collect();
+    assert_eq!(skipped, vec![GameId { app_id: 654321 }]);
+
+    // The run should be recorded along with each of its phases
+    let runs = sync.repo.get_recent_sync_runs(10).await.unwrap();
+    let run = runs.iter().find(|r| r.status == SyncStatus::Succeeded).unwrap();
+    let phases: Vec<(SyncPhase, Option<u64>)> = {
+        run.phases.iter().map(|p| (p.phase, p.item_count)).collect()
+    };
+
+    assert!(run.finished.is_some());
+    assert_eq!(
+        phases,
+        vec![
+            (SyncPhase::SteamGames, Some(4)),
+            (SyncPhase::OwnedGames, Some(2)),
+            (SyncPhase::Wishlist, Some(2)),
+            (SyncPhase::GameDetails, Some(3)),
+            (SyncPhase::Prices, Some(1)),
+            (SyncPhase::PlayedGames, Some(1)),
+            (SyncPhase::Notion, Some(3)),
+        ]
+    );
 
     // TODO: Check the db has been updated as expected
 }
+
+#[tokio::test]
+async fn test_failed_sync_is_recorded() {
+    let conn_str = "host=localhost port=15432 user=tests password=test_admin dbname=rusteam_test";
+    let mut db_client = db::connect(conn_str).await;
+    db::migrate(&mut db_client).await;
+
+    let repo = Repo::new(db_client);
+
+    let mut steam_client = MockSteamClient::new();
+
+    steam_client
+        .expect_get_app_list()
+        .times(AppType::ALL.len())
+        .returning(|t, _| Ok(steam_app_list_by_type(t)));
+
+    steam_client
+        .expect_get_owned_games()
+        .times(1)
+        .returning(|_| Err(SteamError::Conv("Steam went away mid-sync".to_string())));
+
+    let notion_client = MockNotionClient::new();
+
+    let mut sync = Sync::new("STEAMID", repo, Box::new(steam_client), Box::new(notion_client));
+
+    assert!(sync.run().await.is_err());
+
+    // The run should be marked failed, stopping at the phase which failed
+    let runs = sync.repo.get_recent_sync_runs(10).await.unwrap();
+    let run = {
+        runs.iter()
+            .find(|r| r.error.as_deref().is_some_and(|e| e.contains("Steam went away mid-sync")))
+            .unwrap()
+    };
+    let phases: Vec<(SyncPhase, SyncStatus)> = {
+        run.phases.iter().map(|p| (p.phase, p.status.clone())).collect()
+    };
+
+    assert_eq!(run.status, SyncStatus::Failed);
+    assert_eq!(
+        phases,
+        vec![
+            (SyncPhase::SteamGames, SyncStatus::Succeeded),
+            (SyncPhase::OwnedGames, SyncStatus::Failed),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_sync_selected_phases() {
+    let conn_str = "host=localhost port=15432 user=tests password=test_admin dbname=rusteam_test";
+    let mut db_client = db::connect(conn_str).await;
+    db::migrate(&mut db_client).await;
+
+    let repo = Repo::new(db_client);
+
+    let mut steam_client = MockSteamClient::new();
+
+    steam_client
+        .expect_get_owned_games()
+        .times(1)
+        .returning(|_| Ok(steam_owned_games_fixture()));
+
+    steam_client
+        .expect_get_played_games()
+        .times(1)
+        .returning(|_| Ok(steam_played_games_fixture()));
+
+    let notion_client = MockNotionClient::new();
+
+    let mut sync = Sync::new("STEAMID", repo, Box::new(steam_client), Box::new(notion_client));
+
+    // Phases should run in their usual order, whatever order they're asked for in
+    sync.run_phases(&[SyncPhase::PlayedGames, SyncPhase::OwnedGames]).await.unwrap();
+
+    // Other tests may be syncing at the same time, so look for the run with just these phases
+    let runs = sync.repo.get_recent_sync_runs(10).await.unwrap();
+    let run = {
+        runs.iter()
+            .find(|r| r.phases.first().is_some_and(|p| p.phase == SyncPhase::OwnedGames))
+            .unwrap()
+    };
+    let phases: Vec<(SyncPhase, SyncStatus)> = {
+        run.phases.iter().map(|p| (p.phase, p.status.clone())).collect()
+    };
+
+    assert_eq!(run.status, SyncStatus::Succeeded);
+    assert_eq!(
+        phases,
+        vec![
+            (SyncPhase::OwnedGames, SyncStatus::Succeeded),
+            (SyncPhase::PlayedGames, SyncStatus::Succeeded),
+        ]
+    );
+}
+
+#[test]
+fn test_phase_names() {
+    assert_eq!(SyncPhase::try_from("played_games"), Ok(SyncPhase::PlayedGames));
+    assert_eq!(SyncPhase::try_from("playtime"), Ok(SyncPhase::PlayedGames));
+    assert_eq!(SyncPhase::try_from("app-list"), Ok(SyncPhase::SteamGames));
+    assert_eq!(SyncPhase::try_from("game-details"), Ok(SyncPhase::GameDetails));
+    assert!(SyncPhase::try_from("everything").is_err());
+}
+
+#[tokio::test]
+async fn test_dry_run() {
+    let conn_str = "host=localhost port=15432 user=tests password=test_admin dbname=rusteam_test";
+    let mut db_client = db::connect(conn_str).await;
+    db::migrate(&mut db_client).await;
+
+    let repo = Repo::new(db_client);
+
+    let mut steam_client = MockSteamClient::new();
+
+    steam_client
+        .expect_get_owned_games()
+        .times(1)
+        .returning(|_| Ok(vec![GameId { app_id: 8400001 }]));
+
+    // Any attempt to write to notion would fail, as nothing is expected of the mock
+    let notion_client = DryRunNotion::new(MockNotionClient::new());
+    notion_client.set_state("note-id", &GameState::Released).unwrap();
+    notion_client.set_game_details("note-id", "8400001", "Dry Run Simulator").unwrap();
+
+    let mut sync = Sync::new("STEAMID", repo, Box::new(steam_client), Box::new(notion_client));
+
+    sync.repo.begin_transaction().await.unwrap();
+    sync.run_phases(&[SyncPhase::OwnedGames]).await.unwrap();
+    sync.repo.rollback_transaction().await.unwrap();
+
+    let check = db::connect(conn_str).await;
+    let owned = {
+        check
+            .query("SELECT app_id FROM owned_game WHERE app_id = 8400001", &[])
+            .await
+            .unwrap()
+    };
+    assert!(owned.is_empty());
+}
+
+#[tokio::test]
+async fn test_dry_run_survives_failed_statements() {
+    let conn_str = "host=localhost port=15432 user=tests password=test_admin dbname=rusteam_test";
+    let mut db_client = db::connect(conn_str).await;
+    db::migrate(&mut db_client).await;
+
+    let repo = Repo::new(db_client);
+    repo.begin_transaction().await.unwrap();
+
+    // Recording events is allowed to fail, e.g. against a sync run which doesn't exist, and
+    // shouldn't abort the transaction for everything after it as it would by default
+    let event = SyncEvent::Released { game: GameId { app_id: 8400002 } };
+    assert!(repo.insert_sync_events(Some(-1), &[event]).await.is_err());
+
+    repo.insert_owned_games(&[GameId { app_id: 8400002 }]).await.unwrap();
+    let upcoming = repo.get_upcoming_owned_game_ids().await;
+
+    repo.rollback_transaction().await.unwrap();
+    assert!(upcoming.is_ok(), "should still be able to query: {:?}", upcoming.err());
+}
+
+#[tokio::test]
+async fn test_missing_names_from_details() {
+    let conn_str = "host=localhost port=15432 user=tests password=test_admin dbname=rusteam_test";
+    let mut db_client = db::connect(conn_str).await;
+    db::migrate(&mut db_client).await;
+
+    let repo = Repo::new(db_client);
+
+    // Work in a transaction so other tests running alongside don't start tracking these games
+    repo.begin_transaction().await.unwrap();
+
+    let unnamed = GameId { app_id: 8600001 };
+    let named = GameId { app_id: 8600002 };
+    repo.insert_steam_games(HashMap::from([(named.app_id, "Name From App List")])).await.unwrap();
+    repo.insert_owned_games(&[unnamed, named]).await.unwrap();
+
+    assert!(repo.get_tracked_games_missing_names().await.unwrap().contains(&unnamed));
+
+    let mut steam_client = MockSteamClient::new();
+
+    steam_client
+        .expect_get_game_details()
+        .with(predicate::function(|ids: &[GameId]| ids.contains(&GameId { app_id: 8600001 })))
+        .times(1)
+        .returning(|_| {
+            let details = |app_id: u32, name: &str| GameDetails {
+                id: GameId { app_id },
+                name: Some(name.to_string()),
+                app_type: Some("game".to_string()),
+                is_free: Some(true),
+                description: None,
+                header_image: None,
+                controller_support: None,
+                coop: false,
+                local_coop: false,
+                metacritic_percent: None,
+                is_released: true,
+                release_date: Some("1 Jan, 2020".to_string()),
+                release_estimate: None,
+                release_window: None,
+                categories: vec![],
+                genres: vec![],
+                developers: vec![],
+                publishers: vec![],
+                platforms: vec![],
+                recorded: Utc::now(),
+            };
+            Ok((vec![details(8600001, "Name From Details"), details(8600002, "Another Name")], vec![]))
+        });
+
+    let notion_client = MockNotionClient::new();
+
+    let mut sync = Sync::new("STEAMID", repo, Box::new(steam_client), Box::new(notion_client));
+    sync.run_phases(&[SyncPhase::GameDetails]).await.unwrap();
+
+    let names = sync.repo.get_game_names_by_id(&[unnamed, named]).await.unwrap();
+
+    sync.repo.rollback_transaction().await.unwrap();
+
+    // Names from the app list are left as they are
+    assert_eq!(names.get(&unnamed).map(|n| n.as_str()), Some("Name From Details"));
+    assert_eq!(names.get(&named).map(|n| n.as_str()), Some("Name From App List"));
+}
+
+#[tokio::test]
+async fn test_released_detection() {
+    let conn_str = "host=localhost port=15432 user=tests password=test_admin dbname=rusteam_test";
+    let mut db_client = db::connect(conn_str).await;
+    db::migrate(&mut db_client).await;
+
+    let repo = Repo::new(db_client);
+
+    // Work in a transaction so other tests running alongside don't start tracking this game
+    repo.begin_transaction().await.unwrap();
+
+    // An owned pre-order, neither wishlisted nor noted
+    let game = GameId { app_id: 8600003 };
+    let details = move |is_released: bool| GameDetails {
+        id: game,
+        name: Some("Pre-order Simulator".to_string()),
+        app_type: Some("game".to_string()),
+        is_free: Some(false),
+        description: None,
+        header_image: None,
+        controller_support: None,
+        coop: false,
+        local_coop: false,
+        metacritic_percent: None,
+        is_released,
+        release_date: Some("1 Jan, 2020".to_string()),
+        release_estimate: None,
+        release_window: None,
+        categories: vec![],
+        genres: vec![],
+        developers: vec![],
+        publishers: vec![],
+        platforms: vec![],
+        recorded: Utc::now(),
+    };
+    repo.insert_owned_games(&[game]).await.unwrap();
+    repo.insert_game_details(&[details(false)]).await.unwrap();
+
+    let mut steam_client = MockSteamClient::new();
+
+    steam_client
+        .expect_get_game_details()
+        .with(predicate::function(|ids: &[GameId]| ids.contains(&GameId { app_id: 8600003 })))
+        .times(2)
+        .returning(move |_| Ok((vec![details(true)], vec![])));
+
+    let notion_client = MockNotionClient::new();
+
+    let mut sync = Sync::new("STEAMID", repo, Box::new(steam_client), Box::new(notion_client));
+    let first = sync.run_phases(&[SyncPhase::GameDetails]).await.unwrap();
+
+    // Even if steam goes back and forth on it, a game should only be reported released once
+    sync.repo.insert_game_details(&[details(false)]).await.unwrap();
+    let second = sync.run_phases(&[SyncPhase::GameDetails]).await.unwrap();
+
+    sync.repo.rollback_transaction().await.unwrap();
+
+    let released = |events: &[SyncEvent]| -> Vec<GameId> {
+        events
+            .iter()
+            .filter(|e| matches!(e, SyncEvent::Released { .. }))
+            .map(|e| *e.game())
+            .collect()
+    };
+    assert_eq!(released(&first), vec![game]);
+    assert_eq!(released(&second), vec![]);
+}
+
+#[tokio::test]
+async fn test_events_kept_when_later_phase_fails() {
+    let conn_str = "host=localhost port=15432 user=tests password=test_admin dbname=rusteam_test";
+    let mut db_client = db::connect(conn_str).await;
+    db::migrate(&mut db_client).await;
+
+    let repo = Repo::new(db_client);
+    repo.begin_transaction().await.unwrap();
+
+    let started = Utc::now();
+    let game = GameId { app_id: 8600004 };
+    let details = move |is_released: bool| GameDetails {
+        id: game,
+        name: Some("Launch Day Simulator".to_string()),
+        app_type: Some("game".to_string()),
+        is_free: Some(false),
+        description: None,
+        header_image: None,
+        controller_support: None,
+        coop: false,
+        local_coop: false,
+        metacritic_percent: None,
+        is_released,
+        release_date: Some("1 Jan, 2020".to_string()),
+        release_estimate: None,
+        release_window: None,
+        categories: vec![],
+        genres: vec![],
+        developers: vec![],
+        publishers: vec![],
+        platforms: vec![],
+        recorded: Utc::now(),
+    };
+    repo.insert_owned_games(&[game]).await.unwrap();
+    repo.insert_game_details(&[details(false)]).await.unwrap();
+
+    let mut steam_client = MockSteamClient::new();
+
+    // Other tests may commit games to track while this one runs, so only answer for this game
+    steam_client
+        .expect_get_game_details()
+        .returning(move |ids| Ok((ids.contains(&game).then(|| details(true)).into_iter().collect(), vec![])));
+    steam_client
+        .expect_get_prices()
+        .returning(|_| Err(SteamError::Conv("The store fell over".to_string())));
+
+    let notion_client = MockNotionClient::new();
+
+    let mut sync = Sync::new("STEAMID", repo, Box::new(steam_client), Box::new(notion_client));
+    let phases = [SyncPhase::GameDetails, SyncPhase::Prices];
+
+    // The release is saved by the details phase, so it must be kept although prices then fail,
+    // and not be reported again by the next run
+    assert!(sync.run_phases(&phases).await.is_err());
+    assert!(sync.run_phases(&phases).await.is_err());
+
+    let stored = sync.repo.get_sync_events_since(&started).await.unwrap();
+    sync.repo.rollback_transaction().await.unwrap();
+
+    let released: Vec<&SyncEvent> = {
+        stored
+            .iter()
+            .map(|r| &r.event)
+            .filter(|e| *e.game() == game && matches!(e, SyncEvent::Released { .. }))
+            .collect()
+    };
+    assert_eq!(released, vec![&SyncEvent::Released { game }]);
+}
+
+#[tokio::test]
+async fn test_metadata_backfilled_for_released_games() {
+    let conn_str = "host=localhost port=15432 user=tests password=test_admin dbname=rusteam_test";
+    let mut db_client = db::connect(conn_str).await;
+    db::migrate(&mut db_client).await;
+
+    let repo = Repo::new(db_client);
+    repo.begin_transaction().await.unwrap();
+
+    let game = GameId { app_id: 8600005 };
+    let details = move || GameDetails {
+        id: game,
+        name: Some("Old Favourite".to_string()),
+        app_type: Some("game".to_string()),
+        is_free: Some(false),
+        description: None,
+        header_image: None,
+        controller_support: None,
+        coop: false,
+        local_coop: false,
+        metacritic_percent: Some(90),
+        is_released: true,
+        release_date: Some("1 Jan, 2015".to_string()),
+        release_estimate: None,
+        release_window: None,
+        categories: vec![],
+        genres: vec![],
+        developers: vec!["Backfill Dev".to_string()],
+        publishers: vec![],
+        platforms: vec![],
+        recorded: Utc::now(),
+    };
+    repo.insert_steam_games(HashMap::from([(game.app_id, "Old Favourite")])).await.unwrap();
+    repo.insert_owned_games(&[game]).await.unwrap();
+    // Recorded before any metadata was kept, so never stored its developers
+    repo.insert_game_details(&[details()]).await.unwrap();
+
+    let mut steam_client = MockSteamClient::new();
+
+    // Other tests may commit games to track while this one runs, so only answer for this game
+    steam_client
+        .expect_get_game_details()
+        .returning(move |ids| Ok((ids.contains(&game).then(details).into_iter().collect(), vec![])));
+
+    let notion_client = MockNotionClient::new();
+
+    let mut sync = Sync::new("STEAMID", repo, Box::new(steam_client), Box::new(notion_client));
+    assert!(sync.repo.get_games_missing_details().await.unwrap().contains(&game));
+
+    sync.run_phases(&[SyncPhase::GameDetails]).await.unwrap();
+
+    let missing = sync.repo.get_games_missing_details().await.unwrap();
+    let studios = sync.repo.get_studio_release_updates().await.unwrap();
+    sync.repo.rollback_transaction().await.unwrap();
+
+    assert!(!missing.contains(&game));
+    let ours: Vec<(&str, &str)> = {
+        studios
+            .iter()
+            .filter(|u| u.game == game)
+            .map(|u| (u.studio.as_str(), u.role.as_str()))
+            .collect()
+    };
+    assert_eq!(ours, vec![("Backfill Dev", "developer")]);
+}
+
+#[tokio::test]
+async fn test_earlier_release_updates_classified() {
+    let conn_str = "host=localhost port=15432 user=tests password=test_admin dbname=rusteam_test";
+    let mut db_client = db::connect(conn_str).await;
+    db::migrate(&mut db_client).await;
+    let check = db::connect(conn_str).await;
+
+    // As recorded before the kind of change was kept
+    check
+        .execute("INSERT INTO steam_game (app_id, name) VALUES (8600006, 'Eventually') ON CONFLICT DO NOTHING", &[])
+        .await
+        .unwrap();
+    let texts = [
+        ("Out when the stars align", "Q3 2026"),
+        ("Q3 2026", "2027"),
+        ("2027", "Coming soon"),
+    ];
+    for (prev, new) in texts {
+        check
+            .execute(
+                "INSERT INTO release_update (app_id, prev_text, new_text, recorded) VALUES (8600006, $1, $2, NOW())",
+                &[&prev, &new],
+            )
+            .await
+            .unwrap();
+    }
+
+    let mut steam_client = MockSteamClient::new();
+    steam_client.expect_get_game_details().returning(|_| Ok((vec![], vec![])));
+    let notion_client = MockNotionClient::new();
+
+    let mut sync = Sync::new("STEAMID", Repo::new(db_client), Box::new(steam_client), Box::new(notion_client));
+    sync.run_phases(&[SyncPhase::GameDetails]).await.unwrap();
+
+    let changes: Vec<(String, String, Option<String>)> = {
+        check
+            .query("SELECT prev_text, new_text, change FROM release_update WHERE app_id = 8600006 ORDER BY id", &[])
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| (row.get(0), row.get(1), row.get(2)))
+            .collect()
+    };
+    check.execute("DELETE FROM release_update WHERE app_id = 8600006", &[]).await.unwrap();
+    check.execute("DELETE FROM steam_game WHERE app_id = 8600006", &[]).await.unwrap();
+
+    let expected: Vec<(String, String, Option<String>)> = {
+        [
+            // Text the parser can't read says nothing about which way the date moved
+            ("Out when the stars align", "Q3 2026", "changed"),
+            ("Q3 2026", "2027", "postponed"),
+            ("2027", "Coming soon", "tba"),
+        ]
+        .into_iter()
+        .map(|(prev, new, change)| (prev.to_string(), new.to_string(), Some(change.to_string())))
+        .collect()
+    };
+    assert_eq!(changes, expected);
+}
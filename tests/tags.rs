@@ -0,0 +1,100 @@
+use std::collections::HashSet;
+
+use chrono::{TimeZone, Utc};
+use tokio_postgres::Client;
+
+use rusteam::config;
+use rusteam::db;
+use rusteam::db::repo::{GameTagHandling, NotedGamesHandling, Repo};
+use rusteam::models::game::{GameId, NotedGame};
+
+fn config_file() -> String {
+    "test/test-config.toml".to_string()
+}
+
+fn note_fixture(note_id: &str, app_id: Option<u32>, tags: &[&str]) -> NotedGame {
+    NotedGame {
+        note_id: note_id.to_string(),
+        app_id: app_id.map(GameId::from),
+        state: None,
+        tags: tags.iter().map(|t| t.to_string()).collect(),
+        my_rating: None,
+        notes: None,
+        first_noted: Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap(),
+    }
+}
+
+async fn get_game_tags(db_client: &Client) -> HashSet<(String, String)> {
+    let q = r#"
+        SELECT gt.note_id, t.name
+        FROM game_tag gt JOIN tag t ON gt.tag_id = t.id
+        WHERE gt.note_id LIKE 'tags-test-%'
+    "#;
+
+    db_client
+        .query(q, &[])
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| (row.get(0), row.get(1)))
+        .collect()
+}
+
+async fn get_tag_names(db_client: &Client) -> HashSet<String> {
+    db_client
+        .query("SELECT name FROM tag WHERE name LIKE 'Tags test %'", &[])
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| row.get(0))
+        .collect()
+}
+
+fn pairs(expected: &[(&str, &str)]) -> HashSet<(String, String)> {
+    expected.iter().map(|(n, t)| (n.to_string(), t.to_string())).collect()
+}
+
+#[tokio::test]
+async fn test_update_game_tags() {
+    let conf = config::read(Some(&config_file()));
+    let mut db_client = db::connect(&conf.db.connection_string()).await;
+    db::migrate(&mut db_client).await;
+
+    let repo = Repo::new(db_client);
+    let check_client = db::connect(&conf.db.connection_string()).await;
+
+    let notes = vec![
+        note_fixture("tags-test-1", Some(666), &["Tags test roguelike", "Tags test coop"]),
+        note_fixture("tags-test-2", None, &["Tags test coop", "Tags test cosy"]),
+    ];
+    repo.insert_noted_games(&notes).await.unwrap();
+    repo.update_game_tags(&notes).await.unwrap();
+
+    let expected = pairs(&[
+        ("tags-test-1", "Tags test roguelike"),
+        ("tags-test-1", "Tags test coop"),
+        ("tags-test-2", "Tags test coop"),
+        ("tags-test-2", "Tags test cosy"),
+    ]);
+    assert_eq!(get_game_tags(&check_client).await, expected);
+
+    // Removing a tag from a note should remove it from the game, and from the tag table entirely
+    // once no game uses it any more
+    let notes = vec![
+        note_fixture("tags-test-1", Some(666), &["Tags test coop"]),
+        note_fixture("tags-test-2", None, &["Tags test coop", "Tags test cosy"]),
+    ];
+    repo.update_game_tags(&notes).await.unwrap();
+
+    let expected = pairs(&[
+        ("tags-test-1", "Tags test coop"),
+        ("tags-test-2", "Tags test coop"),
+        ("tags-test-2", "Tags test cosy"),
+    ]);
+    assert_eq!(get_game_tags(&check_client).await, expected);
+
+    let expected_names: HashSet<String> = {
+        HashSet::from(["Tags test coop".to_string(), "Tags test cosy".to_string()])
+    };
+    assert_eq!(get_tag_names(&check_client).await, expected_names);
+}
@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use tokio;
+use tokio_postgres::Client;
+
+use rusteam::cli::target_price::RunTargetPrice;
+use rusteam::config;
+use rusteam::db;
+
+fn config_file() -> String {
+    "test/test-config.toml".to_string()
+}
+
+async fn get_target_prices(db_client: &Client) -> HashMap<i64, i32> {
+    db_client
+        .query("SELECT app_id, price FROM target_price", &[])
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| (row.get(0), row.get(1)))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_target_price() {
+    let cfg_file = config_file();
+
+    let set_cmd = |game: &str, price: &str| RunTargetPrice {
+        config_file: Some(PathBuf::from(&cfg_file)),
+        game: game.to_string(),
+        price: Some(price.to_string()),
+        clear: false,
+    };
+
+    set_cmd("123", "9.99").run().await;
+    set_cmd("456", "£20").run().await;
+    set_cmd("123", "7.5").run().await;
+
+    let conf = config::read(Some(&cfg_file));
+    let db_client = db::connect(&conf.db.connection_string()).await;
+
+    let expected: HashMap<i64, i32> = HashMap::from([(123, 750), (456, 2000)]);
+    assert_eq!(get_target_prices(&db_client).await, expected);
+
+    let clear_cmd = RunTargetPrice {
+        config_file: Some(PathBuf::from(&cfg_file)),
+        game: "456".to_string(),
+        price: None,
+        clear: true,
+    };
+    clear_cmd.run().await;
+
+    let expected: HashMap<i64, i32> = HashMap::from([(123, 750)]);
+    assert_eq!(get_target_prices(&db_client).await, expected);
+}